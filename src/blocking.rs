@@ -0,0 +1,87 @@
+use crate::metadata::Metadata;
+use crate::recon::{ReconConfig, ReconError, Source};
+use isbn2::Isbn;
+use std::future::Future;
+
+/// Runs `future` to completion on a dedicated single threaded runtime.
+///
+/// Blocking inside of a runtime would stall its worker thread, so this fails
+/// when called from async code, which should use the async functions instead.
+fn block_on<F, T>(future: F) -> Result<T, ReconError>
+where
+    F: Future<Output = Result<T, ReconError>>,
+{
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(ReconError::Message(
+            "Blocking functions cannot be called from within an async runtime.".to_owned(),
+        ));
+    }
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|err| ReconError::Message(err.to_string()))?
+        .block_on(future)
+}
+
+impl Metadata {
+    /// Blocking version of [`Metadata::from_isbn`]
+    pub fn from_isbn_blocking(sources: &[Source], isbn: &Isbn) -> Result<Metadata, ReconError> {
+        block_on(Self::from_isbn(sources, isbn))
+    }
+
+    /// Blocking version of [`Metadata::from_isbn_with_config`]
+    pub fn from_isbn_with_config_blocking(
+        config: &ReconConfig,
+        sources: &[Source],
+        isbn: &Isbn,
+    ) -> Result<Metadata, ReconError> {
+        block_on(Self::from_isbn_with_config(config, sources, isbn))
+    }
+
+    /// Blocking version of [`Metadata::from_description`]
+    pub fn from_description_blocking(
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+    ) -> Result<Vec<Metadata>, ReconError> {
+        block_on(Self::from_description(search, sources, description))
+    }
+
+    /// Blocking version of [`Metadata::from_description_with_config`]
+    pub fn from_description_with_config_blocking(
+        config: &ReconConfig,
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+    ) -> Result<Vec<Metadata>, ReconError> {
+        block_on(Self::from_description_with_config(
+            config,
+            search,
+            sources,
+            description,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+    use isbn2::Isbn;
+    use std::str::FromStr;
+
+    #[test]
+    fn searches_without_runtime() {
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+
+        let metadata = Metadata::from_isbn_blocking(&[], &isbn).unwrap();
+        assert!(metadata.title.is_empty());
+    }
+
+    #[tokio::test]
+    async fn refuses_to_block_runtime() {
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+
+        assert!(Metadata::from_isbn_blocking(&[], &isbn).is_err());
+    }
+}
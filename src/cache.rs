@@ -0,0 +1,185 @@
+use crate::metadata::Metadata;
+use crate::recon::Source;
+use isbn2::Isbn;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Cache entries are keyed by source and the ISBN string as it was queried
+type Key = (Source, String);
+
+#[derive(Debug)]
+struct Entry {
+    metadata:  Metadata,
+    /// Set for entries of lookups that found nothing
+    expires:   Option<Instant>,
+    last_used: u64,
+}
+
+#[derive(Debug)]
+struct Lru {
+    capacity: usize,
+    entries:  HashMap<Key, Entry>,
+    /// Incremented on every access, the entry with the lowest `last_used` is evicted first
+    tick:     u64,
+}
+
+/// A least recently used, in-memory cache of [`Metadata`] returned by a [`Source`] for an ISBN.
+///
+/// Clones share the same underlying storage, so a single cache can be placed in several
+/// [`ReconConfig`](crate::ReconConfig)s.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    lru:          Arc<Mutex<Lru>>,
+    negative_ttl: Option<Duration>,
+}
+
+impl Cache {
+    /// Creates a cache holding at most `capacity` results.
+    /// Lookups that found nothing are not cached, see [`Cache::cache_not_found`].
+    pub fn new(capacity: usize) -> Self {
+        Cache {
+            lru:          Arc::new(Mutex::new(Lru {
+                capacity,
+                entries: HashMap::with_capacity(capacity),
+                tick: 0,
+            })),
+            negative_ttl: None,
+        }
+    }
+
+    /// Also caches lookups that found nothing, for `ttl` only
+    /// since missing records are often added upstream later on.
+    pub fn cache_not_found(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = Some(ttl);
+        self
+    }
+
+    /// Number of cached results
+    pub fn len(&self) -> usize {
+        self.lru.lock().unwrap().entries.len()
+    }
+
+    /// Returns `true` if nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every cached result
+    pub fn clear(&self) {
+        self.lru.lock().unwrap().entries.clear();
+    }
+
+    pub(crate) fn get(&self, source: &Source, isbn: &Isbn) -> Option<Metadata> {
+        let mut lru = self.lru.lock().unwrap();
+        let key = (*source, isbn.to_string());
+
+        let expired = match lru.entries.get(&key) {
+            Some(entry) => entry.expires.is_some_and(|e| e <= Instant::now()),
+            None => return None,
+        };
+
+        if expired {
+            lru.entries.remove(&key);
+            return None;
+        }
+
+        lru.tick += 1;
+        let tick = lru.tick;
+
+        lru.entries.get_mut(&key).map(|entry| {
+            entry.last_used = tick;
+            entry.metadata.clone()
+        })
+    }
+
+    pub(crate) fn insert(&self, source: &Source, isbn: &Isbn, metadata: &Metadata) {
+        let expires = match (metadata.is_empty(), self.negative_ttl) {
+            (false, _) => None,
+            (true, Some(ttl)) => Some(Instant::now() + ttl),
+            (true, None) => return,
+        };
+
+        let mut lru = self.lru.lock().unwrap();
+        let key = (*source, isbn.to_string());
+
+        if lru.capacity == 0 {
+            return;
+        }
+
+        if !lru.entries.contains_key(&key) && lru.entries.len() >= lru.capacity {
+            let oldest = lru
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest) = oldest {
+                lru.entries.remove(&oldest);
+            }
+        }
+
+        lru.tick += 1;
+        let last_used = lru.tick;
+
+        lru.entries.insert(
+            key,
+            Entry {
+                metadata: metadata.clone(),
+                expires,
+                last_used,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Cache;
+    use crate::metadata::test::titled;
+    use crate::metadata::Metadata;
+    use crate::recon::Source;
+    use isbn2::Isbn;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = Cache::new(2);
+        let a = Isbn::from_str("9781534431003").unwrap();
+        let b = Isbn::from_str("9780765326355").unwrap();
+        let c = Isbn::from_str("9780765365279").unwrap();
+
+        cache.insert(&Source::GoogleBooks, &a, &titled("a"));
+        cache.insert(&Source::GoogleBooks, &b, &titled("b"));
+        assert!(cache.get(&Source::GoogleBooks, &a).is_some());
+
+        cache.insert(&Source::GoogleBooks, &c, &titled("c"));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&Source::GoogleBooks, &a).is_some());
+        assert!(cache.get(&Source::GoogleBooks, &b).is_none());
+        assert!(cache.get(&Source::OpenLibrary, &c).is_none());
+        assert!(cache
+            .get(&Source::GoogleBooks, &c)
+            .unwrap()
+            .title
+            .contains("c"));
+    }
+
+    #[test]
+    fn caches_not_found_only_when_enabled() {
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+
+        let cache = Cache::new(2);
+        cache.insert(&Source::GoogleBooks, &isbn, &Metadata::default());
+        assert!(cache.is_empty());
+
+        let cache = Cache::new(2).cache_not_found(Duration::from_secs(60));
+        cache.insert(&Source::GoogleBooks, &isbn, &Metadata::default());
+        assert!(cache.get(&Source::GoogleBooks, &isbn).is_some());
+
+        let cache = Cache::new(2).cache_not_found(Duration::ZERO);
+        cache.insert(&Source::GoogleBooks, &isbn, &Metadata::default());
+        assert!(cache.get(&Source::GoogleBooks, &isbn).is_none());
+    }
+}
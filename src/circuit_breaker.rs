@@ -0,0 +1,136 @@
+use crate::recon::{ReconError, Source};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct State {
+    /// Failed requests since the last successful one
+    failures: u32,
+    /// Set while requests are short-circuited, until the cooldown after this instant
+    opened:   Option<Instant>,
+}
+
+/// Skips a [`Source`] for a while after it failed several requests in a row,
+/// instead of waiting for every request to it to time out.
+///
+/// Once the cooldown passes a single request is let through as a probe: the circuit closes
+/// again if it succeeds and stays open for another cooldown if it fails.
+/// Lookups that found nothing count as successes.
+///
+/// Clones share the same state, so a single circuit breaker can be placed in several
+/// [`ReconConfig`](crate::ReconConfig)s.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    states:    Arc<Mutex<HashMap<Source, State>>>,
+    threshold: u32,
+    cooldown:  Duration,
+}
+
+impl CircuitBreaker {
+    /// Opens the circuit of a source after `threshold` consecutive failures,
+    /// skipping it for `cooldown`.
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            states: Default::default(),
+            threshold: threshold.max(1),
+            cooldown,
+        }
+    }
+
+    /// Returns `true` if requests to `source` are currently skipped
+    pub fn is_open(&self, source: &Source) -> bool {
+        let states = self.states.lock().unwrap();
+
+        states
+            .get(source)
+            .and_then(|state| state.opened)
+            .is_some_and(|opened| opened.elapsed() < self.cooldown)
+    }
+
+    /// Returns `false` while the circuit of `source` is open. Once the cooldown passed,
+    /// returns `true` once and restarts the cooldown, so a single probe goes through.
+    fn allow(&self, source: &Source) -> bool {
+        let mut states = self.states.lock().unwrap();
+        let state = states.entry(*source).or_default();
+
+        match state.opened {
+            Some(opened) if opened.elapsed() < self.cooldown => false,
+            Some(_) => {
+                state.opened = Some(Instant::now());
+                true
+            }
+            None => true,
+        }
+    }
+
+    fn record(&self, source: &Source, succeeded: bool) {
+        let mut states = self.states.lock().unwrap();
+        let state = states.entry(*source).or_default();
+
+        match succeeded {
+            true => *state = State::default(),
+            false => {
+                state.failures += 1;
+                if state.failures >= self.threshold {
+                    state.opened = Some(Instant::now());
+                }
+            }
+        }
+    }
+
+    /// Awaits `request` to `source` unless its circuit is open,
+    /// recording whether it failed.
+    pub(crate) async fn call<T>(
+        &self,
+        source: &Source,
+        request: impl Future<Output = Result<T, ReconError>>,
+    ) -> Result<T, ReconError> {
+        if !self.allow(source) {
+            return Err(ReconError::Message("source circuit open".to_owned()));
+        }
+
+        let result = request.await;
+        self.record(
+            source,
+            matches!(result, Ok(_) | Err(ReconError::NotFound(_))),
+        );
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CircuitBreaker;
+    use crate::recon::{ReconError, Source};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(50));
+        let fail = || async { Err::<(), _>(ReconError::Message("down".to_owned())) };
+
+        assert!(breaker.call(&Source::Goodreads, fail()).await.is_err());
+        assert!(!breaker.is_open(&Source::Goodreads));
+        assert!(breaker.call(&Source::Goodreads, fail()).await.is_err());
+        assert!(breaker.is_open(&Source::Goodreads));
+        assert!(!breaker.is_open(&Source::Amazon));
+
+        // short-circuited without running the request
+        let skipped = breaker
+            .call(&Source::Goodreads, async { Ok::<_, ReconError>(()) })
+            .await;
+        assert!(matches!(skipped, Err(ReconError::Message(_))));
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // a successful probe closes the circuit
+        assert!(breaker
+            .call(&Source::Goodreads, async { Ok::<_, ReconError>(()) })
+            .await
+            .is_ok());
+        assert!(!breaker.is_open(&Source::Goodreads));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::metadata::{CoverImage, Metadata};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Values of each field found in only one of two [`Metadata`], see [`Metadata::diff`].
+///
+/// Both sides are [`Metadata`] holding only the values the other lacks,
+/// so they are read with the usual accessors.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MetadataDiff {
+    /// Values only present in the [`Metadata`] `diff` was called on
+    pub left:  Metadata,
+    /// Values only present in the [`Metadata`] passed to `diff`
+    pub right: Metadata,
+}
+
+impl MetadataDiff {
+    /// Returns `true` if both [`Metadata`] hold the same values
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
+
+    /// Names of the fields with values in only one of the [`Metadata`],
+    /// named as they serialize, e.g. `["publisher", "page_count"]`
+    pub fn fields(&self) -> Vec<&'static str> {
+        let sides = [&self.left, &self.right];
+        let differs = |field: fn(&Metadata) -> bool| sides.iter().any(|side| !field(side));
+
+        [
+            ("isbn10", differs(|m| m.isbn10.is_empty())),
+            ("isbn13", differs(|m| m.isbn13.is_empty())),
+            ("oclc", differs(|m| m.oclc.is_empty())),
+            ("identifiers", differs(|m| m.identifiers.is_empty())),
+            ("title", differs(|m| m.title.is_empty())),
+            ("subtitle", differs(|m| m.subtitle.is_empty())),
+            ("series", differs(|m| m.series.is_empty())),
+            ("author", differs(|m| m.author.is_empty())),
+            ("description", differs(|m| m.description.is_empty())),
+            ("page_count", differs(|m| m.page_count.is_empty())),
+            ("publisher", differs(|m| m.publisher.is_empty())),
+            (
+                "publication_date",
+                differs(|m| m.publication_date.is_empty()),
+            ),
+            ("language", differs(|m| m.language.is_empty())),
+            ("tag", differs(|m| m.tag.is_empty())),
+            ("cover_image", differs(|m| m.cover_image.is_empty())),
+            ("average_rating", differs(|m| m.average_rating.is_empty())),
+            ("ratings_count", differs(|m| m.ratings_count.is_empty())),
+            (
+                "table_of_contents",
+                differs(|m| m.table_of_contents.is_empty()),
+            ),
+        ]
+        .iter()
+        .filter(|(_, differs)| *differs)
+        .map(|(field, _)| *field)
+        .collect()
+    }
+}
+
+/// Values of `a` missing from `b`
+fn only<T: Hash + Eq + Clone>(a: &HashSet<T>, b: &HashSet<T>) -> HashSet<T> {
+    a.difference(b).cloned().collect()
+}
+
+/// Identifiers of `a` missing from `b`, leaving out catalogs without any
+fn only_identifiers(
+    a: &HashMap<String, HashSet<String>>,
+    b: &HashMap<String, HashSet<String>>,
+) -> HashMap<String, HashSet<String>> {
+    let none = HashSet::new();
+
+    a.iter()
+        .map(|(catalog, ids)| (catalog.clone(), only(ids, b.get(catalog).unwrap_or(&none))))
+        .filter(|(_, ids)| !ids.is_empty())
+        .collect()
+}
+
+fn only_cover_images(a: &CoverImage, b: &CoverImage) -> CoverImage {
+    CoverImage {
+        small_thumbnail: only(&a.small_thumbnail, &b.small_thumbnail),
+        thumbnail:       only(&a.thumbnail, &b.thumbnail),
+        small:           only(&a.small, &b.small),
+        medium:          only(&a.medium, &b.medium),
+        large:           only(&a.large, &b.large),
+        extra_large:     only(&a.extra_large, &b.extra_large),
+    }
+}
+
+/// Values of `a` missing from `b`
+fn only_left(a: &Metadata, b: &Metadata) -> Metadata {
+    Metadata {
+        isbn10:            only(&a.isbn10, &b.isbn10),
+        isbn13:            only(&a.isbn13, &b.isbn13),
+        oclc:              only(&a.oclc, &b.oclc),
+        identifiers:       only_identifiers(&a.identifiers, &b.identifiers),
+        title:             only(&a.title, &b.title),
+        subtitle:          only(&a.subtitle, &b.subtitle),
+        series:            only(&a.series, &b.series),
+        author:            only(&a.author, &b.author),
+        description:       only(&a.description, &b.description),
+        page_count:        only(&a.page_count, &b.page_count),
+        publisher:         only(&a.publisher, &b.publisher),
+        publication_date:  only(&a.publication_date, &b.publication_date),
+        language:          only(&a.language, &b.language),
+        tag:               only(&a.tag, &b.tag),
+        cover_image:       only_cover_images(&a.cover_image, &b.cover_image),
+        average_rating:    only(&a.average_rating, &b.average_rating),
+        ratings_count:     only(&a.ratings_count, &b.ratings_count),
+        table_of_contents: a
+            .table_of_contents
+            .iter()
+            .filter(|entry| !b.table_of_contents.contains(entry))
+            .cloned()
+            .collect(),
+    }
+}
+
+impl Metadata {
+    /// Compares two results field by field, e.g. of two sources from
+    /// [`Metadata::from_isbn_per_source`], to review where they disagree before merging them.
+    ///
+    /// Values are compared exactly, `Tor Books` and `TOR BOOKS` are different publishers.
+    pub fn diff(&self, other: &Metadata) -> MetadataDiff {
+        MetadataDiff {
+            left:  only_left(self, other),
+            right: only_left(other, self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+
+    #[test]
+    fn diffs_fields() {
+        let mut google_books = Metadata::default();
+        google_books.title.insert("Dry".to_owned());
+        google_books
+            .publisher
+            .insert("Simon and Schuster".to_owned());
+        google_books.page_count.insert(400);
+
+        let mut open_library = google_books.clone();
+        open_library.publisher.clear();
+        open_library
+            .publisher
+            .insert("Simon & Schuster Books for Young Readers".to_owned());
+        open_library.tag.insert("dystopian".to_owned());
+
+        let diff = google_books.diff(&open_library);
+        assert!(diff.left.publisher().contains("Simon and Schuster"));
+        assert!(diff
+            .right
+            .publisher()
+            .contains("Simon & Schuster Books for Young Readers"));
+        assert!(diff.left.tag().is_empty());
+        assert!(diff.right.tag().contains("dystopian"));
+        assert!(diff.left.title().is_empty());
+        assert_eq!(diff.fields(), vec!["publisher", "tag"]);
+
+        assert!(google_books.diff(&google_books).is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+use crate::metadata::Metadata;
+use crate::recon::Source;
+use isbn2::Isbn;
+use log::{debug, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A cache of [`Metadata`] returned by a [`Source`] for an ISBN, kept as JSON files in a
+/// directory so results survive restarts.
+///
+/// Entries older than the TTL are considered stale and fetched again. If the directory
+/// cannot be created or written to, nothing is cached and lookups go to the sources.
+#[derive(Clone, Debug)]
+pub struct DiskCache {
+    /// `None` once the directory turned out to be unusable
+    dir: Option<PathBuf>,
+    ttl: Duration,
+}
+
+impl DiskCache {
+    /// Caches results in `dir`, created if missing, for `ttl` each.
+    pub fn new(dir: impl AsRef<Path>, ttl: Duration) -> Self {
+        let dir = dir.as_ref();
+
+        let dir = match fs::create_dir_all(dir) {
+            Ok(()) => Some(dir.to_owned()),
+            Err(err) => {
+                warn!(
+                    "Disk cache disabled, cannot create {}: {}",
+                    dir.display(),
+                    err
+                );
+                None
+            }
+        };
+
+        DiskCache { dir, ttl }
+    }
+
+    /// Returns `false` if the cache directory could not be created
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Removes every cached result
+    pub fn clear(&self) {
+        let entries = match self.dir.as_ref().map(fs::read_dir) {
+            Some(Ok(entries)) => entries,
+            _ => return,
+        };
+
+        for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
+    fn path(&self, source: &Source, isbn: &Isbn) -> Option<PathBuf> {
+        let isbn: String = isbn
+            .to_string()
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect();
+
+        Some(self.dir.as_ref()?.join(format!("{}-{}.json", source, isbn)))
+    }
+
+    pub(crate) fn get(&self, source: &Source, isbn: &Isbn) -> Option<Metadata> {
+        let path = self.path(source, isbn)?;
+
+        let age = fs::metadata(&path)
+            .and_then(|file| file.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+
+        if age >= self.ttl {
+            debug!("Stale disk cache entry {}", path.display());
+            return None;
+        }
+
+        let json = fs::read_to_string(&path).ok()?;
+        match Metadata::from_json(&json) {
+            Ok(metadata) => Some(metadata),
+            Err(err) => {
+                debug!("Unreadable disk cache entry {}: {}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    /// Stores `metadata` unless it is empty, failing silently as the cache is best effort.
+    pub(crate) fn insert(&self, source: &Source, isbn: &Isbn, metadata: &Metadata) {
+        let path = match self.path(source, isbn) {
+            Some(path) if !metadata.is_empty() => path,
+            _ => return,
+        };
+
+        let json = match serde_json::to_string(metadata) {
+            Ok(json) => json,
+            Err(_) => return,
+        };
+
+        // written aside and renamed, so readers never see a partial entry
+        let partial = path.with_extension("json.partial");
+        if let Err(err) = fs::write(&partial, json).and_then(|()| fs::rename(&partial, &path)) {
+            debug!("Cannot write disk cache entry {}: {}", path.display(), err);
+            let _ = fs::remove_file(&partial);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DiskCache;
+    use crate::metadata::test::titled;
+    use crate::metadata::Metadata;
+    use crate::recon::Source;
+    use isbn2::Isbn;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn persists_until_stale() {
+        let dir = std::env::temp_dir().join(format!("recon_disk_cache_{}", std::process::id()));
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+
+        let cache = DiskCache::new(&dir, Duration::from_secs(60));
+        assert!(cache.is_enabled());
+        cache.insert(&Source::GoogleBooks, &isbn, &titled("Dry"));
+        cache.insert(&Source::OpenLibrary, &isbn, &Metadata::default());
+
+        // a new cache over the same directory, as after a restart
+        let reopened = DiskCache::new(&dir, Duration::from_secs(60));
+        assert!(reopened
+            .get(&Source::GoogleBooks, &isbn)
+            .unwrap()
+            .title
+            .contains("Dry"));
+        assert!(reopened.get(&Source::OpenLibrary, &isbn).is_none());
+
+        let stale = DiskCache::new(&dir, Duration::ZERO);
+        assert!(stale.get(&Source::GoogleBooks, &isbn).is_none());
+
+        cache.clear();
+        assert!(cache.get(&Source::GoogleBooks, &isbn).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn degrades_when_unwritable() {
+        let file =
+            std::env::temp_dir().join(format!("recon_disk_cache_{}.file", std::process::id()));
+        std::fs::write(&file, "").unwrap();
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+
+        // a directory cannot be created below a file
+        let cache = DiskCache::new(file.join("cache"), Duration::from_secs(60));
+        assert!(!cache.is_enabled());
+        cache.insert(&Source::GoogleBooks, &isbn, &titled("Dry"));
+        assert!(cache.get(&Source::GoogleBooks, &isbn).is_none());
+        let _ = std::fs::remove_file(&file);
+    }
+}
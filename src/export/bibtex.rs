@@ -0,0 +1,141 @@
+use crate::metadata::Metadata;
+
+/// Escapes characters with a special meaning in BibTeX field values
+fn escape(value: &str) -> String {
+    value
+        .chars()
+        .fold(String::with_capacity(value.len()), |mut escaped, c| {
+            match c {
+                '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                // no escaped form, written as text commands instead
+                '\\' => escaped.push_str("\\textbackslash{}"),
+                '~' => escaped.push_str("\\textasciitilde{}"),
+                '^' => escaped.push_str("\\textasciicircum{}"),
+                c => escaped.push(c),
+            }
+            escaped
+        })
+}
+
+/// Surname of an author written either as "First Last" or "Last, First"
+fn surname(author: &str) -> &str {
+    match author.split_once(',') {
+        Some((surname, _)) => surname.trim(),
+        None => author.split_whitespace().last().unwrap_or_default(),
+    }
+}
+
+impl Metadata {
+    /// Formats [`Metadata`] as a BibTeX `@book` entry.
+    ///
+    /// The citation key is made of the first author's surname and the publication year,
+    /// e.g. `sanderson2010`. Fields without a value are omitted and fields with
+    /// several values use the first one in sorted order, except authors which are
+    /// all joined by ` and `.
+    pub fn to_bibtex(&self) -> String {
+        let mut authors = self.author.iter().collect::<Vec<_>>();
+        authors.sort();
+
+        let year = self.publication_date.iter().min().map(|date| date.year());
+
+        let key = authors
+            .first()
+            .map(|author| surname(author))
+            .unwrap_or("book")
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .chain(year.map(|y| y.to_string()).unwrap_or_default().chars())
+            .collect::<String>();
+
+        let isbn = match self.isbn13.iter().map(|isbn13| isbn13.to_string()).min() {
+            Some(isbn13) => Some(isbn13),
+            None => self.isbn10.iter().map(|isbn10| isbn10.to_string()).min(),
+        };
+
+        let fields = [
+            ("title", self.title.iter().min().cloned()),
+            ("subtitle", self.subtitle.iter().min().cloned()),
+            (
+                "author",
+                Some(
+                    authors
+                        .iter()
+                        .map(|author| author.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" and "),
+                )
+                .filter(|authors| !authors.is_empty()),
+            ),
+            ("publisher", self.publisher.iter().min().cloned()),
+            ("year", year.map(|y| y.to_string())),
+            ("isbn", isbn),
+        ];
+
+        let fields = fields
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .as_ref()
+                    .map(|value| format!("  {} = {{{}}}", name, escape(value)))
+            })
+            .collect::<Vec<_>>();
+
+        format!("@book{{{},\n{}\n}}\n", key, fields.join(",\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+    use crate::metadata::PubDate;
+    use chrono::NaiveDate;
+    use isbn2::Isbn13;
+    use std::str::FromStr;
+
+    #[test]
+    fn formats_bibtex() {
+        let mut metadata = Metadata::default();
+        metadata.title.insert("The Way of Kings".to_owned());
+        metadata.author.insert("Brandon Sanderson".to_owned());
+        metadata.publisher.insert("Tor Books".to_owned());
+        metadata
+            .isbn13
+            .insert(Isbn13::from_str("9780765326355").unwrap());
+        metadata
+            .publication_date
+            .insert(PubDate::Full(NaiveDate::from_ymd_opt(2010, 8, 31).unwrap()));
+
+        assert_eq!(
+            metadata.to_bibtex(),
+            "@book{sanderson2010,\n  title = {The Way of Kings},\n  author = {Brandon Sanderson},\n  publisher = {Tor Books},\n  year = {2010},\n  isbn = {9780765326355}\n}\n"
+        );
+    }
+
+    #[test]
+    fn omits_missing_fields() {
+        let mut metadata = Metadata::default();
+        metadata.title.insert("Tips & Tricks".to_owned());
+
+        assert_eq!(
+            metadata.to_bibtex(),
+            "@book{book,\n  title = {Tips \\& Tricks}\n}\n"
+        );
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        let mut metadata = Metadata::default();
+        metadata
+            .title
+            .insert(r"C:\Books ~ x^2 & 100% {of} $5 #1 a_b".to_owned());
+
+        assert_eq!(
+            metadata.to_bibtex(),
+            "@book{book,\n  title = {C:\\textbackslash{}Books \\textasciitilde{} x\\textasciicircum{}2 \\& 100\\% \\{of\\} \\$5 \\#1 a\\_b}\n}\n"
+        );
+    }
+}
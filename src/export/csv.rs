@@ -0,0 +1,142 @@
+use crate::metadata::Metadata;
+use std::collections::HashSet;
+use std::io;
+
+/// Separates multiple values of a field within a single column
+const SEPARATOR: &str = "; ";
+
+/// Joins every value of a field in sorted order, so records of the same [`Metadata`] are equal
+fn join<T: ToString>(values: &HashSet<T>) -> String {
+    let mut values = values.iter().map(T::to_string).collect::<Vec<_>>();
+    values.sort();
+    values.join(SEPARATOR)
+}
+
+/// Quotes `field` if it contains a delimiter, quote or line break, doubling inner quotes
+fn escape(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Writes a single CSV row terminated by a newline
+fn write_row<W, I, S>(writer: &mut W, fields: I) -> io::Result<()>
+where
+    W: io::Write,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let row = fields
+        .into_iter()
+        .map(|field| escape(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    writeln!(writer, "{}", row)
+}
+
+impl Metadata {
+    /// Column names of [`Metadata::to_csv_record`], in order
+    pub const CSV_HEADER: &'static [&'static str] = &[
+        "isbn10",
+        "isbn13",
+        "title",
+        "subtitle",
+        "series",
+        "author",
+        "description",
+        "page_count",
+        "publisher",
+        "publication_date",
+        "language",
+        "tag",
+        "cover_image",
+        "average_rating",
+        "ratings_count",
+    ];
+
+    /// Flattens [`Metadata`] into a row of columns described by [`Metadata::CSV_HEADER`].
+    ///
+    /// Multiple values of a field are sorted and joined by `; `, dates are written
+    /// in their narrowest form, e.g. `2011` or `2011-03`, and only the largest cover image is kept.
+    pub fn to_csv_record(&self) -> Vec<String> {
+        vec![
+            join(&self.isbn10),
+            join(&self.isbn13),
+            join(&self.title),
+            join(&self.subtitle),
+            join(&self.series),
+            join(&self.author),
+            join(&self.description),
+            join(&self.page_count),
+            join(&self.publisher),
+            join(&self.publication_date), // `PubDate` displays as `%Y`, `%Y-%m` or `%Y-%m-%d`
+            join(&self.language),
+            join(&self.tag),
+            self.cover_image.best_url().unwrap_or_default().to_owned(),
+            join(&self.average_rating),
+            join(&self.ratings_count),
+        ]
+    }
+
+    /// Writes a header followed by a record of each [`Metadata`] as CSV to `writer`.
+    pub fn write_csv<W: io::Write>(mut writer: W, metadata: &[Metadata]) -> io::Result<()> {
+        write_row(&mut writer, Self::CSV_HEADER)?;
+
+        for m in metadata {
+            write_row(&mut writer, m.to_csv_record())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+    use crate::metadata::PubDate;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn writes_csv() {
+        let mut metadata = Metadata::default();
+        metadata.title.insert("The Way of Kings".to_owned());
+        metadata.author.insert("Sanderson, Brandon".to_owned());
+        metadata.tag.insert("fantasy".to_owned());
+        metadata.tag.insert("epic-fantasy".to_owned());
+        metadata
+            .description
+            .insert("Roshar is a world of \"stone and storms\".".to_owned());
+        metadata
+            .publication_date
+            .insert(PubDate::Full(NaiveDate::from_ymd_opt(2010, 8, 31).unwrap()));
+        metadata
+            .cover_image
+            .medium
+            .insert("https://example.com/medium.jpg".to_owned());
+        metadata
+            .cover_image
+            .large
+            .insert("https://example.com/large.jpg".to_owned());
+
+        let record = metadata.to_csv_record();
+        assert_eq!(record.len(), Metadata::CSV_HEADER.len());
+        assert_eq!(record[11], "epic-fantasy; fantasy");
+
+        let mut csv = Vec::new();
+        Metadata::write_csv(&mut csv, &[metadata]).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(lines.next(), Some(Metadata::CSV_HEADER.join(",").as_str()));
+        assert_eq!(
+            lines.next(),
+            Some(
+                ",,The Way of Kings,,,\"Sanderson, Brandon\",\"Roshar is a world of \"\"stone and storms\"\".\",,,2010-08-31,,epic-fantasy; fantasy,https://example.com/large.jpg,,"
+            )
+        );
+        assert_eq!(lines.next(), None);
+    }
+}
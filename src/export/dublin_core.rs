@@ -0,0 +1,81 @@
+use crate::export::escape_xml;
+use crate::metadata::Metadata;
+use std::collections::HashSet;
+
+/// Appends a `<dc:element>` for every value in sorted order
+fn push_elements<T: ToString>(xml: &mut String, element: &str, values: &HashSet<T>) {
+    let mut values = values.iter().map(T::to_string).collect::<Vec<_>>();
+    values.sort();
+
+    for value in values {
+        xml.push_str(&format!(
+            "  <dc:{0}>{1}</dc:{0}>\n",
+            element,
+            escape_xml(&value)
+        ));
+    }
+}
+
+impl Metadata {
+    /// Formats [`Metadata`] as an OAI-PMH `oai_dc` Dublin Core record,
+    /// with ISBNs as `urn:isbn:` identifiers.
+    ///
+    /// Fields with several values produce an element per value and fields
+    /// without a value are omitted.
+    pub fn to_dublin_core(&self) -> String {
+        let mut xml = String::from(
+            "<oai_dc:dc xmlns:oai_dc=\"http://www.openarchives.org/OAI/2.0/oai_dc/\" \
+             xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n",
+        );
+
+        push_elements(&mut xml, "title", &self.title);
+        push_elements(&mut xml, "creator", &self.author);
+        push_elements(&mut xml, "publisher", &self.publisher);
+        push_elements(&mut xml, "date", &self.publication_date);
+        push_elements(
+            &mut xml,
+            "identifier",
+            &self
+                .isbn13
+                .iter()
+                .map(|isbn| format!("urn:isbn:{}", isbn))
+                .chain(self.isbn10.iter().map(|isbn| format!("urn:isbn:{}", isbn)))
+                .collect(),
+        );
+        push_elements(&mut xml, "language", &self.language);
+        push_elements(&mut xml, "subject", &self.tag);
+        push_elements(&mut xml, "description", &self.description);
+
+        xml.push_str("</oai_dc:dc>\n");
+
+        xml
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+    use crate::metadata::PubDate;
+
+    #[test]
+    fn formats_dublin_core() {
+        let mut metadata = Metadata::default();
+        metadata.title.insert("Good Omens".to_owned());
+        metadata.author.insert("Terry Pratchett".to_owned());
+        metadata.author.insert("Neil Gaiman".to_owned());
+        metadata.publisher.insert("Gollancz & Co".to_owned());
+        metadata.publication_date.insert(PubDate::Year(1990));
+
+        assert_eq!(
+            metadata.to_dublin_core(),
+            "<oai_dc:dc xmlns:oai_dc=\"http://www.openarchives.org/OAI/2.0/oai_dc/\" \
+             xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n  \
+             <dc:title>Good Omens</dc:title>\n  \
+             <dc:creator>Neil Gaiman</dc:creator>\n  \
+             <dc:creator>Terry Pratchett</dc:creator>\n  \
+             <dc:publisher>Gollancz &amp; Co</dc:publisher>\n  \
+             <dc:date>1990</dc:date>\n\
+             </oai_dc:dc>\n"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::metadata::Metadata;
+use crate::recon::ReconError;
+use serde::{ser, Serialize, Serializer};
+
+/// Serializes a [`Metadata`] without its empty fields, see [`Metadata::sparse`]
+#[derive(Debug, Clone, Copy)]
+pub struct SparseMetadata<'a>(&'a Metadata);
+
+/// Removes empty arrays and objects, including objects emptied by the removal
+fn prune(value: &mut serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, value| !prune(value));
+            map.is_empty()
+        }
+        serde_json::Value::Array(array) => array.is_empty(),
+        _ => false,
+    }
+}
+
+impl Serialize for SparseMetadata<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = serde_json::to_value(self.0).map_err(ser::Error::custom)?;
+        prune(&mut value);
+
+        value.serialize(serializer)
+    }
+}
+
+impl Metadata {
+    /// Serializable view of [`Metadata`] that omits empty fields and cover image sizes,
+    /// instead of the fixed schema with empty arrays [`Metadata`] serializes to.
+    /// Omitted fields are read back as empty.
+    ///
+    /// ```
+    /// use recon_metadata::Metadata;
+    ///
+    /// let json = serde_json::to_string(&Metadata::default().sparse()).unwrap();
+    /// assert_eq!(json, "{}");
+    /// ```
+    pub fn sparse(&self) -> SparseMetadata<'_> {
+        SparseMetadata(self)
+    }
+
+    /// Serializes [`Metadata`] as compact JSON, readable back with [`Metadata::from_json`].
+    pub fn to_json(&self) -> Result<String, ReconError> {
+        serde_json::to_string(self).map_err(ReconError::JSONParse)
+    }
+
+    /// Serializes [`Metadata`] as indented JSON, readable back with [`Metadata::from_json`].
+    pub fn to_json_pretty(&self) -> Result<String, ReconError> {
+        serde_json::to_string_pretty(self).map_err(ReconError::JSONParse)
+    }
+
+    /// Parses [`Metadata`] serialized by [`Metadata::to_json`], [`Metadata::to_json_pretty`]
+    /// or [`Metadata::sparse`]. Records of a source's own API are parsed by
+    /// [`Metadata::from_source_json`] instead.
+    pub fn from_json(json: &str) -> Result<Metadata, ReconError> {
+        serde_json::from_str(json).map_err(ReconError::JSONParse)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn serializes_json() {
+        use crate::metadata::Metadata;
+
+        let mut metadata = Metadata::default();
+        metadata
+            .title
+            .insert("This Is How You Lose the Time War".to_owned());
+
+        let json = metadata.to_json().unwrap();
+        assert!(!json.contains('\n'));
+
+        let pretty = metadata.to_json_pretty().unwrap();
+        assert!(pretty.contains('\n'));
+
+        for json in [json, pretty] {
+            assert_eq!(Metadata::from_json(&json).unwrap(), metadata);
+        }
+
+        assert!(matches!(
+            Metadata::from_json("{ \"title\": "),
+            Err(crate::recon::ReconError::JSONParse(_))
+        ));
+    }
+
+    #[test]
+    fn skips_empty_fields() {
+        use crate::metadata::Metadata;
+
+        let mut metadata = Metadata::default();
+        metadata
+            .title
+            .insert("This Is How You Lose the Time War".to_owned());
+        metadata
+            .cover_image
+            .large
+            .insert("https://covers.openlibrary.org/b/id/9383993-L.jpg".to_owned());
+
+        let value = serde_json::to_value(metadata.sparse()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "title": ["This Is How You Lose the Time War"],
+                "cover_image": { "large": ["https://covers.openlibrary.org/b/id/9383993-L.jpg"] }
+            })
+        );
+
+        let parsed: Metadata = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.title, metadata.title);
+        assert_eq!(parsed.cover_image, metadata.cover_image);
+        assert!(parsed.tag.is_empty());
+    }
+}
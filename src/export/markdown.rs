@@ -0,0 +1,96 @@
+use crate::metadata::Metadata;
+use std::collections::HashSet;
+
+/// Values in sorted order joined with `, `, `None` if there are none
+fn joined<T: ToString>(values: &HashSet<T>) -> Option<String> {
+    let mut values = values.iter().map(T::to_string).collect::<Vec<_>>();
+    values.sort();
+
+    match values.is_empty() {
+        true => None,
+        false => Some(values.join(", ")),
+    }
+}
+
+impl Metadata {
+    /// Formats [`Metadata`] as a Markdown summary: the title as a heading, authors,
+    /// the best cover image, a list of publishing details, tags and descriptions.
+    ///
+    /// Sections and list items without a value are omitted.
+    pub fn to_markdown(&self) -> String {
+        let mut sections = Vec::new();
+
+        if let Some(title) = joined(&self.title) {
+            sections.push(format!("# {}", title));
+        }
+
+        if let Some(author) = joined(&self.author) {
+            sections.push(format!("**{}**", author));
+        }
+
+        if let Some(url) = self.cover_image.best_url() {
+            sections.push(format!("![cover]({})", url));
+        }
+
+        let isbns = self
+            .isbn13
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.isbn10.iter().map(ToString::to_string))
+            .collect::<HashSet<_>>();
+
+        let details = [
+            ("Publisher", joined(&self.publisher)),
+            ("Published", joined(&self.publication_date)),
+            ("Pages", joined(&self.page_count)),
+            ("Language", joined(&self.language)),
+            ("ISBN", joined(&isbns)),
+        ]
+        .iter()
+        .filter_map(|(name, value)| value.as_ref().map(|value| format!("- {}: {}", name, value)))
+        .collect::<Vec<_>>();
+
+        if !details.is_empty() {
+            sections.push(details.join("\n"));
+        }
+
+        if let Some(tags) = joined(&self.tag) {
+            sections.push(format!("Tags: {}", tags));
+        }
+
+        let mut descriptions = self.description.iter().collect::<Vec<_>>();
+        descriptions.sort();
+        sections.extend(descriptions.into_iter().cloned());
+
+        let mut markdown = sections.join("\n\n");
+        markdown.push('\n');
+
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+
+    #[test]
+    fn formats_markdown() {
+        let mut metadata = Metadata::default();
+        metadata.title.insert("Piranesi".to_owned());
+        metadata.author.insert("Susanna Clarke".to_owned());
+        metadata.publisher.insert("Bloomsbury".to_owned());
+        metadata.page_count.insert(272);
+        metadata.tag.insert("fantasy".to_owned());
+        metadata.description.insert("The House is vast.".to_owned());
+
+        assert_eq!(
+            metadata.to_markdown(),
+            "# Piranesi\n\n\
+             **Susanna Clarke**\n\n\
+             - Publisher: Bloomsbury\n\
+             - Pages: 272\n\n\
+             Tags: fantasy\n\n\
+             The House is vast.\n"
+        );
+    }
+}
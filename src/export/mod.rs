@@ -0,0 +1,26 @@
+/// BibTeX entries of [`Metadata`](crate::Metadata) for citations
+pub(crate) mod bibtex;
+/// CSV records of [`Metadata`](crate::Metadata) for spreadsheets and catalog imports
+pub(crate) mod csv;
+/// Dublin Core XML of [`Metadata`](crate::Metadata) for repositories and archives
+pub(crate) mod dublin_core;
+/// JSON of [`Metadata`](crate::Metadata) for storage and APIs
+pub(crate) mod json;
+/// Markdown summaries of [`Metadata`](crate::Metadata) for notes and static sites
+pub(crate) mod markdown;
+/// ONIX for Books 3.0 products of [`Metadata`](crate::Metadata) for publishers and distributors
+pub(crate) mod onix;
+/// RIS records of [`Metadata`](crate::Metadata) for reference managers
+pub(crate) mod ris;
+/// schema.org JSON-LD of [`Metadata`](crate::Metadata) for structured data on web pages
+pub(crate) mod schema_org;
+
+/// Escapes the characters XML reserves for markup
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
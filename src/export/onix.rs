@@ -0,0 +1,185 @@
+use crate::export::escape_xml;
+use crate::metadata::{Metadata, PubDate};
+use isbn2::Isbn13;
+
+/// ONIX `<Date>` element of a publication date, with its `dateformat` if not `YYYYMMDD`
+fn onix_date(date: &PubDate) -> String {
+    match date {
+        PubDate::Year(year) => format!("<Date dateformat=\"05\">{:04}</Date>", year),
+        PubDate::YearMonth(year, month) => {
+            format!("<Date dateformat=\"01\">{:04}{:02}</Date>", year, month)
+        }
+        PubDate::Full(date) => format!("<Date>{}</Date>", date.format("%Y%m%d")),
+    }
+}
+
+impl Metadata {
+    /// Formats [`Metadata`] as a minimal ONIX for Books 3.0 `<Product>`,
+    /// to be placed in an `<ONIXMessage>`.
+    ///
+    /// ONIX allows a single title, so the first title and subtitle in sorted order are used,
+    /// along with the earliest publication date and the largest page count.
+    /// ISBN-10s are listed as their ISBN-13 and composites without a value are omitted.
+    pub fn to_onix(&self) -> String {
+        let mut isbns = self
+            .isbn13
+            .iter()
+            .copied()
+            .chain(self.isbn10.iter().map(|isbn10| Isbn13::from(*isbn10)))
+            .map(|isbn13| isbn13.to_string())
+            .collect::<Vec<_>>();
+        isbns.sort();
+        isbns.dedup();
+
+        let title = self.title.iter().min();
+        let reference = isbns.first().or(title).cloned().unwrap_or_default();
+
+        let mut onix = String::from("<Product>\n");
+        onix.push_str(&format!(
+            "  <RecordReference>{}</RecordReference>\n",
+            escape_xml(&reference)
+        ));
+        onix.push_str("  <NotificationType>03</NotificationType>\n");
+
+        for isbn in &isbns {
+            onix.push_str(&format!(
+                "  <ProductIdentifier>\
+                 <ProductIDType>15</ProductIDType><IDValue>{}</IDValue>\
+                 </ProductIdentifier>\n",
+                isbn
+            ));
+        }
+
+        onix.push_str("  <DescriptiveDetail>\n");
+        onix.push_str("    <ProductComposition>00</ProductComposition>\n");
+        onix.push_str("    <ProductForm>BA</ProductForm>\n");
+
+        if let Some(title) = title {
+            let subtitle = self
+                .subtitle
+                .iter()
+                .min()
+                .map(|subtitle| format!("<Subtitle>{}</Subtitle>", escape_xml(subtitle)))
+                .unwrap_or_default();
+
+            onix.push_str(&format!(
+                "    <TitleDetail><TitleType>01</TitleType>\
+                 <TitleElement><TitleElementLevel>01</TitleElementLevel>\
+                 <TitleText>{}</TitleText>{}</TitleElement></TitleDetail>\n",
+                escape_xml(title),
+                subtitle
+            ));
+        }
+
+        let mut authors = self.author.iter().collect::<Vec<_>>();
+        authors.sort();
+
+        for (sequence, author) in authors.into_iter().enumerate() {
+            onix.push_str(&format!(
+                "    <Contributor><SequenceNumber>{}</SequenceNumber>\
+                 <ContributorRole>A01</ContributorRole><PersonName>{}</PersonName></Contributor>\n",
+                sequence + 1,
+                escape_xml(author)
+            ));
+        }
+
+        if let Some(pages) = self.page_count.iter().max() {
+            onix.push_str(&format!(
+                "    <Extent><ExtentType>00</ExtentType>\
+                 <ExtentValue>{}</ExtentValue><ExtentUnit>03</ExtentUnit></Extent>\n",
+                pages
+            ));
+        }
+
+        let mut tags = self.tag.iter().collect::<Vec<_>>();
+        tags.sort();
+
+        for tag in tags {
+            // scheme `20` is keywords
+            onix.push_str(&format!(
+                "    <Subject><SubjectSchemeIdentifier>20</SubjectSchemeIdentifier>\
+                 <SubjectHeadingText>{}</SubjectHeadingText></Subject>\n",
+                escape_xml(tag)
+            ));
+        }
+
+        onix.push_str("  </DescriptiveDetail>\n");
+
+        let date = self.publication_date.iter().min();
+
+        if !self.publisher.is_empty() || date.is_some() {
+            onix.push_str("  <PublishingDetail>\n");
+
+            let mut publishers = self.publisher.iter().collect::<Vec<_>>();
+            publishers.sort();
+
+            for publisher in publishers {
+                onix.push_str(&format!(
+                    "    <Publisher><PublishingRole>01</PublishingRole>\
+                     <PublisherName>{}</PublisherName></Publisher>\n",
+                    escape_xml(publisher)
+                ));
+            }
+
+            if let Some(date) = date {
+                onix.push_str(&format!(
+                    "    <PublishingDate><PublishingDateRole>01</PublishingDateRole>{}\
+                     </PublishingDate>\n",
+                    onix_date(date)
+                ));
+            }
+
+            onix.push_str("  </PublishingDetail>\n");
+        }
+
+        onix.push_str("</Product>\n");
+
+        onix
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::{Metadata, PubDate};
+    use isbn2::Isbn13;
+    use std::str::FromStr;
+
+    #[test]
+    fn formats_onix() {
+        let mut metadata = Metadata::default();
+        metadata
+            .isbn13
+            .insert(Isbn13::from_str("9781534431003").unwrap());
+        metadata
+            .title
+            .insert("This Is How You Lose the Time War".to_owned());
+        metadata.author.insert("Max Gladstone".to_owned());
+        metadata.author.insert("Amal El-Mohtar".to_owned());
+        metadata.publisher.insert("Saga Press".to_owned());
+        metadata
+            .publication_date
+            .insert(PubDate::YearMonth(2019, 7));
+        metadata.page_count.insert(209);
+
+        assert_eq!(
+            metadata.to_onix(),
+            "<Product>\n  \
+             <RecordReference>9781534431003</RecordReference>\n  \
+             <NotificationType>03</NotificationType>\n  \
+             <ProductIdentifier><ProductIDType>15</ProductIDType><IDValue>9781534431003</IDValue></ProductIdentifier>\n  \
+             <DescriptiveDetail>\n    \
+             <ProductComposition>00</ProductComposition>\n    \
+             <ProductForm>BA</ProductForm>\n    \
+             <TitleDetail><TitleType>01</TitleType><TitleElement><TitleElementLevel>01</TitleElementLevel><TitleText>This Is How You Lose the Time War</TitleText></TitleElement></TitleDetail>\n    \
+             <Contributor><SequenceNumber>1</SequenceNumber><ContributorRole>A01</ContributorRole><PersonName>Amal El-Mohtar</PersonName></Contributor>\n    \
+             <Contributor><SequenceNumber>2</SequenceNumber><ContributorRole>A01</ContributorRole><PersonName>Max Gladstone</PersonName></Contributor>\n    \
+             <Extent><ExtentType>00</ExtentType><ExtentValue>209</ExtentValue><ExtentUnit>03</ExtentUnit></Extent>\n  \
+             </DescriptiveDetail>\n  \
+             <PublishingDetail>\n    \
+             <Publisher><PublishingRole>01</PublishingRole><PublisherName>Saga Press</PublisherName></Publisher>\n    \
+             <PublishingDate><PublishingDateRole>01</PublishingDateRole><Date dateformat=\"01\">201907</Date></PublishingDate>\n  \
+             </PublishingDetail>\n\
+             </Product>\n"
+        );
+    }
+}
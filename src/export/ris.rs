@@ -0,0 +1,77 @@
+use crate::metadata::Metadata;
+use std::collections::HashSet;
+
+/// Appends a `TAG  - value` line for every value in sorted order
+fn push_lines<T: ToString>(ris: &mut String, tag: &str, values: &HashSet<T>) {
+    let mut values = values.iter().map(T::to_string).collect::<Vec<_>>();
+    values.sort();
+
+    for value in values {
+        // a line break would start a new tag
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        ris.push_str(&format!("{}  - {}\n", tag, value));
+    }
+}
+
+impl Metadata {
+    /// Formats [`Metadata`] as an RIS `BOOK` record.
+    ///
+    /// Fields with several values produce a line per value and fields
+    /// without a value are omitted.
+    pub fn to_ris(&self) -> String {
+        let mut ris = String::from("TY  - BOOK\n");
+
+        push_lines(&mut ris, "TI", &self.title);
+        push_lines(&mut ris, "AU", &self.author);
+        push_lines(&mut ris, "PB", &self.publisher);
+        push_lines(
+            &mut ris,
+            "PY",
+            &self
+                .publication_date
+                .iter()
+                .map(|date| date.year())
+                .collect(),
+        );
+        push_lines(&mut ris, "SN", &self.isbn13);
+        push_lines(&mut ris, "SN", &self.isbn10);
+        push_lines(&mut ris, "AB", &self.description);
+        push_lines(&mut ris, "KW", &self.tag);
+
+        ris.push_str("ER  - \n");
+
+        ris
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+    use crate::metadata::PubDate;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn formats_ris() {
+        let mut metadata = Metadata::default();
+        metadata
+            .title
+            .insert("This Is How You Lose the Time War".to_owned());
+        metadata.author.insert("Max Gladstone".to_owned());
+        metadata.author.insert("Amal El-Mohtar".to_owned());
+        metadata.tag.insert("science-fiction".to_owned());
+        metadata
+            .publication_date
+            .insert(PubDate::Full(NaiveDate::from_ymd_opt(2019, 7, 16).unwrap()));
+
+        assert_eq!(
+            metadata.to_ris(),
+            "TY  - BOOK\n\
+             TI  - This Is How You Lose the Time War\n\
+             AU  - Amal El-Mohtar\n\
+             AU  - Max Gladstone\n\
+             PY  - 2019\n\
+             KW  - science-fiction\n\
+             ER  - \n"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::metadata::Metadata;
+use crate::representative::sorted;
+use serde_json::{json, Map, Value};
+
+impl Metadata {
+    /// Formats [`Metadata`] as a schema.org `Book` in JSON-LD,
+    /// <https://schema.org/Book>
+    ///
+    /// Authors, publishers, ISBNs, images, languages and subjects are arrays,
+    /// name, description, publication date and number of pages take the
+    /// value picked by [`Metadata::representative`]. Fields without a value are omitted.
+    pub fn to_schema_org_jsonld(&self) -> Value {
+        let one = self.representative();
+
+        let mut book = Map::new();
+        book.insert("@context".to_owned(), json!("https://schema.org"));
+        book.insert("@type".to_owned(), json!("Book"));
+
+        let mut insert = |key: &str, value: Value| {
+            let empty = match &value {
+                Value::Null => true,
+                Value::Array(values) => values.is_empty(),
+                _ => false,
+            };
+            if !empty {
+                book.insert(key.to_owned(), value);
+            }
+        };
+
+        let mut isbn13s = self
+            .isbn13
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        let mut isbn10s = self
+            .isbn10
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        isbn13s.sort();
+        isbn10s.sort();
+        let isbns = isbn13s.into_iter().chain(isbn10s).collect::<Vec<_>>();
+
+        insert("name", json!(one.title));
+        insert(
+            "author",
+            Value::Array(
+                one.author
+                    .iter()
+                    .map(|name| json!({ "@type": "Person", "name": name }))
+                    .collect(),
+            ),
+        );
+        insert("isbn", json!(isbns));
+        insert(
+            "datePublished",
+            json!(one.publication_date.map(|date| date.to_string())),
+        );
+        insert(
+            "publisher",
+            Value::Array(
+                sorted(self.publisher.iter())
+                    .iter()
+                    .map(|name| json!({ "@type": "Organization", "name": name }))
+                    .collect(),
+            ),
+        );
+        insert("numberOfPages", json!(one.page_count));
+        insert("image", json!(self.cover_image.iter().collect::<Vec<_>>()));
+        insert("description", json!(one.description));
+        insert("inLanguage", json!(sorted(self.language.iter())));
+        insert("about", json!(one.tag));
+
+        Value::Object(book)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+    use isbn2::Isbn13;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn formats_schema_org_jsonld() {
+        let mut metadata = Metadata::default();
+        metadata.title.insert("The Way of Kings".to_owned());
+        metadata.author.insert("Brandon Sanderson".to_owned());
+        metadata
+            .isbn13
+            .insert(Isbn13::from_str("9780765326355").unwrap());
+        metadata.page_count.insert(1007);
+
+        assert_eq!(
+            metadata.to_schema_org_jsonld(),
+            json!({
+                "@context": "https://schema.org",
+                "@type": "Book",
+                "name": "The Way of Kings",
+                "author": [{ "@type": "Person", "name": "Brandon Sanderson" }],
+                "isbn": ["9780765326355"],
+                "numberOfPages": 1007
+            })
+        );
+    }
+}
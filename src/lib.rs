@@ -1,7 +1,6 @@
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 #![warn(missing_debug_implementations)]
-#![warn(missing_debug_implementations)]
 
 /*!
 # Usage
@@ -53,16 +52,62 @@ async fn main() {
 ```
 */
 
+/// Blocking versions of the async search functions
+#[cfg(feature = "blocking")]
+pub(crate) mod blocking;
+/// In-memory cache of results returned by sources
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "cache")]
+pub use cache::Cache;
+/// Cache of results returned by sources, kept on disk
+#[cfg(feature = "disk_cache")]
+pub mod disk_cache;
+#[cfg(feature = "disk_cache")]
+pub use disk_cache::DiskCache;
+/// Skipping of sources that keep failing
+pub mod circuit_breaker;
+pub use circuit_breaker::CircuitBreaker;
+/// Sharing of identical source requests between concurrent lookups
+pub mod single_flight;
+pub use single_flight::SingleFlight;
+/// Conversion of metadata into formats of other tools
+pub(crate) mod export;
+pub use export::json::SparseMetadata;
 /// Book metadata returned by database and search APIs
 pub mod metadata;
+pub use metadata::CoverImage;
 pub use metadata::Metadata;
+pub use metadata::PubDate;
+pub use metadata::SearchResults;
+/// Single value views of book metadata
+pub mod representative;
+pub use representative::MetadataOne;
+/// Field by field comparison of book metadata
+pub mod diff;
+pub use diff::MetadataDiff;
 /// Types required by `recon_metadata`
 pub mod recon;
+pub use recon::ReconConfig;
 pub use recon::ReconError;
 pub use recon::Source;
+/// Token cancelling [`Metadata::from_isbn_cancellable`] and [`Metadata::from_description_cancellable`]
+pub use tokio_util::sync::CancellationToken;
 /// API and database sources
 pub(crate) mod source;
+#[cfg(feature = "mock")]
+pub use source::mock::Mock;
 /// Utility functions used for type conversion and field translation
+// helpers specific to a source are unused when its feature is disabled
+#[cfg_attr(
+    not(all(
+        feature = "google_books",
+        feature = "open_library",
+        feature = "goodreads",
+        feature = "amazon"
+    )),
+    allow(dead_code)
+)]
 pub(crate) mod util;
 
 #[cfg(test)]
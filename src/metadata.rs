@@ -1,19 +1,27 @@
 use crate::recon::Source;
 use crate::{
-    recon::ReconError,
-    source::{google_books::GoogleBooks, open_library::OpenLibrary},
+    recon::{ReconConfig, ReconError},
+    source::{book_source, IsbnsWithTotal},
 };
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
 use futures::future::join_all;
+use futures::stream::{self, FuturesUnordered, StreamExt};
 use isbn2::{Isbn, Isbn10, Isbn13};
+use log::debug;
 use serde::ser::SerializeSeq;
-use serde::{Serialize, Serializer};
-use std::collections::HashSet;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::iter::{FromIterator, Sum};
 use std::ops::Add;
+use std::str::FromStr;
+use tokio_util::sync::CancellationToken;
 
 /// Information about type types of cover images according to their size
-#[derive(Debug, Default, Serialize, PartialEq, Eq, Clone)]
-pub(crate) struct CoverImage {
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct CoverImage {
     pub(crate) small_thumbnail: HashSet<String>,
     pub(crate) thumbnail:       HashSet<String>,
     pub(crate) small:           HashSet<String>,
@@ -23,6 +31,36 @@ pub(crate) struct CoverImage {
 }
 
 impl CoverImage {
+    /// URLs of small thumbnail sized images
+    pub fn small_thumbnail(&self) -> &HashSet<String> {
+        &self.small_thumbnail
+    }
+
+    /// URLs of thumbnail sized images
+    pub fn thumbnail(&self) -> &HashSet<String> {
+        &self.thumbnail
+    }
+
+    /// URLs of small images
+    pub fn small(&self) -> &HashSet<String> {
+        &self.small
+    }
+
+    /// URLs of medium images
+    pub fn medium(&self) -> &HashSet<String> {
+        &self.medium
+    }
+
+    /// URLs of large images
+    pub fn large(&self) -> &HashSet<String> {
+        &self.large
+    }
+
+    /// URLs of extra large images
+    pub fn extra_large(&self) -> &HashSet<String> {
+        &self.extra_large
+    }
+
     pub(crate) fn extend(&mut self, other: Self) -> &mut Self {
         self.small_thumbnail.extend(other.small_thumbnail);
         self.thumbnail.extend(other.thumbnail);
@@ -33,6 +71,218 @@ impl CoverImage {
 
         self
     }
+
+    /// Iterates over every size from largest to smallest, including empty ones,
+    /// paired with its name as serialized, e.g. `("extra_large", urls)`.
+    pub fn sizes(&self) -> impl Iterator<Item = (&'static str, &HashSet<String>)> {
+        IntoIterator::into_iter([
+            ("extra_large", &self.extra_large),
+            ("large", &self.large),
+            ("medium", &self.medium),
+            ("small", &self.small),
+            ("thumbnail", &self.thumbnail),
+            ("small_thumbnail", &self.small_thumbnail),
+        ])
+    }
+
+    /// URL of an image of the largest size available
+    pub fn best_url(&self) -> Option<&str> {
+        self.sizes()
+            .find_map(|(_, images)| images.iter().min()) // `min` for a stable pick within a size
+            .map(String::as_str)
+    }
+
+    /// Removes URLs of smaller sizes that only differ from a URL of a larger size by
+    /// size parameters such as GoogleBooks' `zoom`, keeping the largest variant.
+    pub fn dedup_by_base(&mut self) {
+        /// URL without size parameters
+        fn base(url: &str) -> String {
+            match url.split_once('?') {
+                Some((path, query)) => {
+                    let query = query
+                        .split('&')
+                        .filter(|param| {
+                            let key = param.split('=').next().unwrap_or_default();
+                            !matches!(key, "zoom" | "edge" | "w" | "h")
+                        })
+                        .collect::<Vec<_>>();
+
+                    match query.is_empty() {
+                        true => path.to_owned(),
+                        false => format!("{}?{}", path, query.join("&")),
+                    }
+                }
+                None => url.to_owned(),
+            }
+        }
+
+        let mut seen = HashSet::new();
+
+        for images in [
+            &mut self.extra_large,
+            &mut self.large,
+            &mut self.medium,
+            &mut self.small,
+            &mut self.thumbnail,
+            &mut self.small_thumbnail,
+        ] {
+            // keeping every URL of a size, they are only duplicates of larger sizes
+            let bases = images.iter().map(|url| base(url)).collect::<Vec<_>>();
+            images.retain(|url| !seen.contains(&base(url)));
+            seen.extend(bases);
+        }
+    }
+
+    /// Iterates over the URLs of images of every size, from largest to smallest
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.sizes().flat_map(|(_, images)| images)
+    }
+
+    /// Downloads the image of the largest size available, returning its raw bytes.
+    /// Fails with [`ReconError::MissingField`] if there are no images of any size.
+    pub async fn download_largest(&self, client: &reqwest::Client) -> Result<Vec<u8>, ReconError> {
+        let url = self
+            .best_url()
+            .ok_or_else(|| ReconError::MissingField("cover_image".to_owned()))?;
+
+        debug!("Request: {:#?}", &url);
+
+        let bytes = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(ReconError::connection)?
+            .bytes()
+            .await
+            .map_err(ReconError::connection)?;
+
+        Ok(bytes.to_vec())
+    }
+
+    /// Returns `true` if there is no URL of any size
+    pub fn is_empty(&self) -> bool {
+        self.small_thumbnail.is_empty()
+            && self.thumbnail.is_empty()
+            && self.small.is_empty()
+            && self.medium.is_empty()
+            && self.large.is_empty()
+            && self.extra_large.is_empty()
+    }
+}
+
+/// Results of a descriptive search, see [`Metadata::from_description_with_total`]
+#[derive(Debug, Default, Clone)]
+pub struct SearchResults {
+    /// Number of matching books reported by the search source, or the number of ISBNs
+    /// it returned if it does not report one. Usually more than `results` hold.
+    pub total:   usize,
+    /// Books found, in the order the search source ranked them
+    pub results: Vec<Metadata>,
+}
+
+/// A publication date, only as precise as the source gave it.
+///
+/// Displays and serializes to its narrowest form, `2011`, `2011-03` or `2011-03-15`,
+/// and orders chronologically, less precise dates before the ones they contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PubDate {
+    /// Only the year is known
+    Year(i32),
+    /// The year and month are known
+    YearMonth(i32, u32),
+    /// The day is known
+    Full(NaiveDate),
+}
+
+impl PubDate {
+    /// Year of publication
+    pub fn year(&self) -> i32 {
+        match self {
+            PubDate::Year(year) | PubDate::YearMonth(year, _) => *year,
+            PubDate::Full(date) => date.year(),
+        }
+    }
+
+    /// Month of publication, if known
+    pub fn month(&self) -> Option<u32> {
+        match self {
+            PubDate::Year(_) => None,
+            PubDate::YearMonth(_, month) => Some(*month),
+            PubDate::Full(date) => Some(date.month()),
+        }
+    }
+
+    /// First day of the period the date covers
+    pub fn first_day(&self) -> Option<NaiveDate> {
+        match self {
+            PubDate::Year(year) => NaiveDate::from_ymd_opt(*year, 1, 1),
+            PubDate::YearMonth(year, month) => NaiveDate::from_ymd_opt(*year, *month, 1),
+            PubDate::Full(date) => Some(*date),
+        }
+    }
+}
+
+impl Ord for PubDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the precision breaks ties between e.g. `2011-03` and `2011-03-01`
+        let precision = |date: &PubDate| match date {
+            PubDate::Year(_) => 0,
+            PubDate::YearMonth(..) => 1,
+            PubDate::Full(_) => 2,
+        };
+        let key = |date: &PubDate| (date.year(), date.month(), date.first_day(), precision(date));
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for PubDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for PubDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PubDate::Year(year) => write!(f, "{:04}", year),
+            PubDate::YearMonth(year, month) => write!(f, "{:04}-{:02}", year, month),
+            PubDate::Full(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+impl FromStr for PubDate {
+    type Err = ReconError;
+
+    /// Parses the narrowest forms, `2011`, `2011-03` or `2011-03-15`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        // partial dates are completed to validate them with `NaiveDate`
+        match s.matches('-').count() {
+            0 => NaiveDate::parse_from_str(&format!("{}-01-01", s), "%Y-%m-%d")
+                .map(|date| PubDate::Year(date.year())),
+            1 => NaiveDate::parse_from_str(&format!("{}-01", s), "%Y-%m-%d")
+                .map(|date| PubDate::YearMonth(date.year(), date.month())),
+            _ => NaiveDate::parse_from_str(s, "%Y-%m-%d").map(PubDate::Full),
+        }
+        .map_err(ReconError::DateParse)
+    }
+}
+
+impl Serialize for PubDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PubDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
 }
 
 /// [`Metadata`] type contains information to uniquely identify a book.
@@ -40,47 +290,55 @@ impl CoverImage {
 /// Contains one or multiple of the following:
 ///  1. ISBN10
 ///  2. ISBN13
-///  3. Title
-///  4. Author
-///  5. Description
-///  6. Page count
-///  7. Publisher
-///  8. Publication Date
-///  9. Language
-/// 10. Tag
-/// 11. Cover image
-#[derive(Debug, Default, Serialize)]
+///  3. OCLC number
+///  4. Other identifiers
+///  5. Title
+///  6. Subtitle
+///  7. Series
+///  8. Author
+///  9. Description
+/// 10. Page count
+/// 11. Publisher
+/// 12. Publication Date
+/// 13. Language
+/// 14. Tag
+/// 15. Cover image
+/// 16. Average rating
+/// 17. Ratings count
+/// 18. Table of contents
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
 pub struct Metadata {
-    #[serde(serialize_with = "serialize_hashset_isbn10")]
-    pub(crate) isbn10:           HashSet<Isbn10>,
-    #[serde(serialize_with = "serialize_hashset_isbn13")]
-    pub(crate) isbn13:           HashSet<Isbn13>,
-    pub(crate) title:            HashSet<String>,
-    pub(crate) author:           HashSet<String>,
-    pub(crate) description:      HashSet<String>,
-    pub(crate) page_count:       HashSet<u16>,
-    pub(crate) publisher:        HashSet<String>,
-    #[serde(serialize_with = "serialize_hashset_naivedate")]
-    pub(crate) publication_date: HashSet<NaiveDate>,
-    pub(crate) language:         HashSet<String>,
-    pub(crate) tag:              HashSet<String>,
-    pub(crate) cover_image:      CoverImage,
-}
-
-fn serialize_hashset_naivedate<S>(
-    dates: &HashSet<NaiveDate>,
-    serializer: S,
-) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    let mut seq = serializer.serialize_seq(Some(dates.len()))?;
-
-    for date in dates {
-        let s = date.format("%Y-%m-%d").to_string();
-        seq.serialize_element(&s)?;
-    }
-    seq.end()
+    #[serde(
+        serialize_with = "serialize_hashset_isbn10",
+        deserialize_with = "deserialize_hashset_isbn10"
+    )]
+    pub(crate) isbn10:            HashSet<Isbn10>,
+    #[serde(
+        serialize_with = "serialize_hashset_isbn13",
+        deserialize_with = "deserialize_hashset_isbn13"
+    )]
+    pub(crate) isbn13:            HashSet<Isbn13>,
+    /// WorldCat identifiers, which libraries catalogue books by
+    pub(crate) oclc:              HashSet<String>,
+    /// Identifiers in other catalogs keyed by catalog, e.g. `lccn` or `goodreads`
+    pub(crate) identifiers:       HashMap<String, HashSet<String>>,
+    pub(crate) title:             HashSet<String>,
+    pub(crate) subtitle:          HashSet<String>,
+    pub(crate) series:            HashSet<String>,
+    pub(crate) author:            HashSet<String>,
+    pub(crate) description:       HashSet<String>,
+    pub(crate) page_count:        HashSet<u16>,
+    pub(crate) publisher:         HashSet<String>,
+    pub(crate) publication_date:  HashSet<PubDate>,
+    pub(crate) language:          HashSet<String>,
+    pub(crate) tag:               HashSet<String>,
+    pub(crate) cover_image:       CoverImage,
+    /// Average rating as shown by the source, e.g. `"4.32"` out of 5
+    pub(crate) average_rating:    HashSet<String>,
+    pub(crate) ratings_count:     HashSet<u32>,
+    /// Entries in reading order, from a single source as editions differ in their chapters
+    pub(crate) table_of_contents: Vec<String>,
 }
 
 fn serialize_hashset_isbn10<S>(isbn10s: &HashSet<Isbn10>, serializer: S) -> Result<S::Ok, S::Error>
@@ -109,13 +367,67 @@ where
     seq.end()
 }
 
+fn deserialize_hashset_isbn10<'de, D>(deserializer: D) -> Result<HashSet<Isbn10>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|s| Isbn10::from_str(s).map_err(de::Error::custom))
+        .collect()
+}
+
+fn deserialize_hashset_isbn13<'de, D>(deserializer: D) -> Result<HashSet<Isbn13>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|s| Isbn13::from_str(s).map_err(de::Error::custom))
+        .collect()
+}
+
+/// Completes once `cancel` is triggered, never without one
+async fn cancelled(cancel: Option<&CancellationToken>) {
+    match cancel {
+        Some(cancel) => cancel.cancelled().await,
+        None => futures::future::pending().await,
+    }
+}
+
+fn cancelled_error() -> ReconError {
+    ReconError::Message("Search cancelled".to_owned())
+}
+
+/// Removes repeated ISBNs keeping the first occurrence,
+/// an ISBN10 and the ISBN13 it converts to are considered the same.
+fn dedup_isbns(isbns: &[Isbn]) -> Vec<&Isbn> {
+    let mut seen = HashSet::new();
+
+    isbns
+        .iter()
+        .filter(|isbn| {
+            seen.insert(match isbn {
+                Isbn::_10(isbn10) => Isbn13::from(*isbn10),
+                Isbn::_13(isbn13) => *isbn13,
+            })
+        })
+        .collect()
+}
+
 impl Add for Metadata {
     type Output = Self;
 
     fn add(mut self, other: Self) -> Self {
         self.isbn10.extend(other.isbn10);
         self.isbn13.extend(other.isbn13);
+        self.oclc.extend(other.oclc);
+        for (catalog, ids) in other.identifiers {
+            self.identifiers.entry(catalog).or_default().extend(ids);
+        }
         self.title.extend(other.title);
+        self.subtitle.extend(other.subtitle);
+        self.series.extend(other.series);
         self.author.extend(other.author);
         self.description.extend(other.description);
         self.page_count.extend(other.page_count);
@@ -124,51 +436,630 @@ impl Add for Metadata {
         self.language.extend(other.language);
         self.tag.extend(other.tag);
         self.cover_image.extend(other.cover_image);
+        self.average_rating.extend(other.average_rating);
+        self.ratings_count.extend(other.ratings_count);
+        if self.table_of_contents.is_empty() {
+            self.table_of_contents = other.table_of_contents;
+        }
 
         self
     }
 }
 
+impl FromIterator<Metadata> for Metadata {
+    /// Merges every [`Metadata`] with [`Add`], starting from an empty one
+    fn from_iter<I: IntoIterator<Item = Metadata>>(iter: I) -> Self {
+        iter.into_iter().fold(Metadata::default(), Add::add)
+    }
+}
+
+impl Sum for Metadata {
+    fn sum<I: Iterator<Item = Metadata>>(iter: I) -> Self {
+        iter.collect()
+    }
+}
+
+/// Similarity of two titles or publishers from `0.0` to `1.0`: the share of words they have
+/// in common, ignoring case, punctuation and a trailing `(Series #N)`.
+fn similarity(a: &str, b: &str) -> f32 {
+    fn words(s: &str) -> HashSet<String> {
+        crate::util::translater::split_series(s)
+            .0
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    let (a, b) = (words(a), words(b));
+    match a.union(&b).count() {
+        0 => 1.0,
+        union => a.intersection(&b).count() as f32 / union as f32,
+    }
+}
+
+/// Keeps the longest of every group of values at least `threshold` similar to each other
+fn collapse_similar(values: &mut HashSet<String>, threshold: f32) {
+    let mut sorted = values.drain().collect::<Vec<_>>();
+    // longest first, as the more complete form, ties broken alphabetically to stay deterministic
+    sorted.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+    for value in sorted {
+        if !values
+            .iter()
+            .any(|kept| similarity(kept, &value) >= threshold)
+        {
+            values.insert(value);
+        }
+    }
+}
+
 impl Metadata {
+    /// ISBN10s of the book
+    pub fn isbn10(&self) -> &HashSet<Isbn10> {
+        &self.isbn10
+    }
+
+    /// ISBN13s of the book
+    pub fn isbn13(&self) -> &HashSet<Isbn13> {
+        &self.isbn13
+    }
+
+    /// OCLC numbers of the book, identifying it in WorldCat <https://www.worldcat.org>
+    pub fn oclc(&self) -> &HashSet<String> {
+        &self.oclc
+    }
+
+    /// Identifiers of the book in other catalogs, keyed by catalog such as `lccn`,
+    /// `goodreads` or `librarything`. ISBNs and OCLC numbers have fields of their own.
+    pub fn identifiers(&self) -> &HashMap<String, HashSet<String>> {
+        &self.identifiers
+    }
+
+    /// Titles, without subtitle or series
+    pub fn title(&self) -> &HashSet<String> {
+        &self.title
+    }
+
+    /// Subtitles
+    pub fn subtitle(&self) -> &HashSet<String> {
+        &self.subtitle
+    }
+
+    /// Series the book belongs to, e.g. `The Stormlight Archive #1`
+    pub fn series(&self) -> &HashSet<String> {
+        &self.series
+    }
+
+    /// Authors
+    pub fn author(&self) -> &HashSet<String> {
+        &self.author
+    }
+
+    /// Descriptions or synopses
+    pub fn description(&self) -> &HashSet<String> {
+        &self.description
+    }
+
+    /// Page counts of the editions found
+    pub fn page_count(&self) -> &HashSet<u16> {
+        &self.page_count
+    }
+
+    /// Publishers
+    pub fn publisher(&self) -> &HashSet<String> {
+        &self.publisher
+    }
+
+    /// Publication dates
+    pub fn publication_date(&self) -> &HashSet<PubDate> {
+        &self.publication_date
+    }
+
+    /// Languages, as ISO 639-1 codes where recognized
+    pub fn language(&self) -> &HashSet<String> {
+        &self.language
+    }
+
+    /// Lowercase, hyphenated subjects and genres
+    pub fn tag(&self) -> &HashSet<String> {
+        &self.tag
+    }
+
+    /// BISAC subject codes of the tags with a known heading, e.g. `FIC028000`
+    /// for `science-fiction`. Other tags are left out rather than guessed.
+    pub fn bisac_codes(&self) -> HashSet<String> {
+        crate::util::translater::tags_to_bisac(&self.tag)
+    }
+
+    /// Cover images by size
+    pub fn cover_image(&self) -> &CoverImage {
+        &self.cover_image
+    }
+
+    /// Average ratings as shown by the source, e.g. `"4.32"` out of 5
+    pub fn average_rating(&self) -> &HashSet<String> {
+        &self.average_rating
+    }
+
+    /// Number of ratings the average is made of
+    pub fn ratings_count(&self) -> &HashSet<u32> {
+        &self.ratings_count
+    }
+
+    /// Entries of the table of contents in reading order. Merged results keep the table of
+    /// the first source that has one, since entries of different editions do not interleave.
+    pub fn table_of_contents(&self) -> &[String] {
+        &self.table_of_contents
+    }
+
+    /// Returns `true` if no field holds a value, e.g. for a book none of the sources found
+    pub fn is_empty(&self) -> bool {
+        self.isbn10.is_empty()
+            && self.isbn13.is_empty()
+            && self.oclc.is_empty()
+            && self.identifiers.is_empty()
+            && self.title.is_empty()
+            && self.subtitle.is_empty()
+            && self.series.is_empty()
+            && self.author.is_empty()
+            && self.description.is_empty()
+            && self.page_count.is_empty()
+            && self.publisher.is_empty()
+            && self.publication_date.is_empty()
+            && self.language.is_empty()
+            && self.tag.is_empty()
+            && self.cover_image.is_empty()
+            && self.average_rating.is_empty()
+            && self.ratings_count.is_empty()
+            && self.table_of_contents.is_empty()
+    }
+
+    /// Similarity [`Metadata::merge_all`] collapses titles and publishers at,
+    /// high enough to only merge differences in case, punctuation and series suffixes.
+    pub const MERGE_THRESHOLD: f32 = 0.9;
+
+    /// Merges every [`Metadata`] like [`Add`], then collapses titles and publishers that
+    /// are near duplicates of each other, such as `The Way of Kings` and
+    /// `The Way of Kings (Stormlight Archive, #1)`, into their longest form.
+    pub fn merge_all<I: IntoIterator<Item = Metadata>>(iter: I) -> Metadata {
+        Self::merge_all_with_threshold(iter, Self::MERGE_THRESHOLD)
+    }
+
+    /// Same as [`Metadata::merge_all`] collapsing values at least `threshold` similar,
+    /// from `0.0` to `1.0`, where similarity is the share of words two values have in common.
+    pub fn merge_all_with_threshold<I: IntoIterator<Item = Metadata>>(
+        iter: I,
+        threshold: f32,
+    ) -> Metadata {
+        let mut metadata = iter.into_iter().collect::<Metadata>();
+
+        collapse_similar(&mut metadata.title, threshold);
+        collapse_similar(&mut metadata.publisher, threshold);
+
+        metadata
+    }
+
+    /// Cross-populates `isbn10` and `isbn13`:
+    /// every ISBN10 is converted to its ISBN13 and every `978` prefixed ISBN13 to its ISBN10.
+    pub fn normalize_isbns(&mut self) {
+        let isbn13s = self
+            .isbn10
+            .iter()
+            .map(|isbn10| Isbn13::from(*isbn10))
+            .collect::<Vec<_>>();
+        let isbn10s = self
+            .isbn13
+            .iter()
+            .filter_map(|isbn13| Isbn10::try_from(*isbn13).ok()) // discarding `979` prefixes
+            .collect::<Vec<_>>();
+
+        self.isbn13.extend(isbn13s);
+        self.isbn10.extend(isbn10s);
+    }
+
+    /// Scores how closely titles, subtitles and authors match `query`, from `0.0` to `1.0`.
+    ///
+    /// The score is the share of distinct words of `query` found among the words of those
+    /// fields, compared case-insensitively and ignoring punctuation. It only depends on its
+    /// inputs, so equal results always score the same.
+    pub fn relevance(&self, query: &str) -> f32 {
+        fn words(s: &str) -> impl Iterator<Item = String> + '_ {
+            s.split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+        }
+
+        let query = words(query).collect::<HashSet<_>>();
+        if query.is_empty() {
+            return 0.0;
+        }
+
+        let found = self
+            .title
+            .iter()
+            .chain(&self.subtitle)
+            .chain(&self.author)
+            .flat_map(|s| words(s))
+            .collect::<HashSet<_>>();
+
+        query.intersection(&found).count() as f32 / query.len() as f32
+    }
+
+    /// Pairs each of `results` with its [`Metadata::relevance`] to `query`, closest match first.
+    /// Results scoring the same keep their order, usually the ranking of the search source.
+    pub fn rank_by_relevance(results: Vec<Metadata>, query: &str) -> Vec<(f32, Metadata)> {
+        let mut ranked = results
+            .into_iter()
+            .map(|metadata| (metadata.relevance(query), metadata))
+            .collect::<Vec<_>>();
+
+        ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        ranked
+    }
+
     async fn description_from_source(
+        client: &reqwest::Client,
+        config: &ReconConfig,
         source: &Source,
         description: &str,
+    ) -> Result<IsbnsWithTotal, ReconError> {
+        let book_source = book_source(source);
+        let request = book_source.from_description_with_total(client, config, description);
+
+        #[cfg(feature = "tracing")]
+        let request = tracing::Instrument::instrument(
+            request,
+            tracing::info_span!("source", source = %source, description),
+        );
+
+        Self::through_circuit_breaker(config, source, request).await
+    }
+
+    async fn title_author_from_source(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        source: &Source,
+        title: &str,
+        author: &str,
     ) -> Result<Vec<Isbn>, ReconError> {
-        match source {
-            Source::GoogleBooks => GoogleBooks::from_description(description).await,
-            Source::OpenLibrary => OpenLibrary::from_description(description).await,
-            Source::Amazon => unimplemented!(),
-            Source::Goodreads => {
-                todo!("fix Goodreads::from_description(description).await, tendrill error")
-            }
+        let book_source = book_source(source);
+        let request = book_source.from_title_author(client, config, title, author);
+
+        #[cfg(feature = "tracing")]
+        let request = tracing::Instrument::instrument(
+            request,
+            tracing::info_span!("source", source = %source, title, author),
+        );
+
+        Self::through_circuit_breaker(config, source, request).await
+    }
+
+    /// Awaits `request` through [`ReconConfig::circuit_breaker`], if any
+    async fn through_circuit_breaker<T>(
+        config: &ReconConfig,
+        source: &Source,
+        request: impl std::future::Future<Output = Result<T, ReconError>>,
+    ) -> Result<T, ReconError> {
+        match &config.circuit_breaker {
+            Some(circuit_breaker) => circuit_breaker.call(source, request).await,
+            None => request.await,
         }
     }
 
-    async fn isbn_from_source(source: &Source, isbn: &Isbn) -> Result<Metadata, ReconError> {
-        match source {
-            Source::GoogleBooks => GoogleBooks::from_isbn(isbn).await,
-            Source::OpenLibrary => OpenLibrary::from_isbn(isbn).await,
-            Source::Amazon => unimplemented!(),
-            Source::Goodreads => todo!("fix Goodreads::from_isbn(isbn).await, tendrill error"),
+    async fn isbn_from_source(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        source: &Source,
+        isbn: &Isbn,
+    ) -> Result<Metadata, ReconError> {
+        #[cfg(feature = "cache")]
+        if let Some(metadata) = config.cache.as_ref().and_then(|c| c.get(source, isbn)) {
+            debug!("Cache hit: {:?} {}", source, isbn);
+            return match metadata.is_empty() {
+                true => Err(ReconError::NotFound(isbn.clone())),
+                false => Ok(metadata),
+            };
+        }
+
+        #[cfg(feature = "disk_cache")]
+        if let Some(metadata) = config.disk_cache.as_ref().and_then(|c| c.get(source, isbn)) {
+            debug!("Disk cache hit: {:?} {}", source, isbn);
+            return Ok(metadata);
+        }
+
+        let book_source = book_source(source);
+        let request = book_source.from_isbn(client, config, isbn);
+
+        #[cfg(feature = "tracing")]
+        let request = tracing::Instrument::instrument(
+            request,
+            tracing::info_span!("source", source = %source, isbn = %isbn),
+        );
+
+        let request = Self::through_circuit_breaker(config, source, request);
+        let metadata = match &config.single_flight {
+            Some(single_flight) => single_flight.call(source, isbn, request).await,
+            None => request.await,
+        };
+
+        #[cfg(feature = "cache")]
+        if let Some(cache) = &config.cache {
+            match &metadata {
+                Ok(metadata) => cache.insert(source, isbn, metadata),
+                // cached as empty, only kept if caching of lookups that found nothing is enabled
+                Err(ReconError::NotFound(_)) => cache.insert(source, isbn, &Metadata::default()),
+                Err(_) => {}
+            }
         }
+
+        #[cfg(feature = "disk_cache")]
+        if let (Some(disk_cache), Ok(metadata)) = (&config.disk_cache, &metadata) {
+            disk_cache.insert(source, isbn, metadata);
+        }
+
+        metadata
     }
 
     /// Performs parallel ISBN search.
     /// First arg requires a list of [`Source`],
     /// second an `Isbn`.
     /// Combines information for a complete and exasutive result [`Metadata`].
+    /// Sources that fail are left out of the result, an error is returned only if all of them fail.
     pub async fn from_isbn(sources: &[Source], isbn: &Isbn) -> Result<Metadata, ReconError> {
-        let mut metadata = Metadata::default();
+        Self::from_isbn_with_config(&ReconConfig::default(), sources, isbn).await
+    }
+
+    /// Same as [`Metadata::from_isbn`] but the ISBN is parsed from a string,
+    /// ignoring hyphens and whitespace, e.g. `"978-1-5344-3100-3"`.
+    pub async fn from_isbn_str(sources: &[Source], isbn: &str) -> Result<Metadata, ReconError> {
+        let isbn = isbn
+            .chars()
+            .filter(|c| *c != '-' && !c.is_whitespace())
+            .collect::<String>();
+        let isbn = Isbn::from_str(&isbn).map_err(ReconError::ISBNParse)?;
+
+        Self::from_isbn(sources, &isbn).await
+    }
+
+    /// Performs [`Metadata::from_isbn`] for each of `isbns`, at most
+    /// [`ReconConfig::max_concurrent_isbns`] at a time.
+    /// Returns every ISBN paired with its own result, in the order given.
+    pub async fn from_isbns(
+        sources: &[Source],
+        isbns: &[Isbn],
+    ) -> Vec<(Isbn, Result<Metadata, ReconError>)> {
+        Self::from_isbns_with_config(&ReconConfig::default(), sources, isbns).await
+    }
+
+    /// Same as [`Metadata::from_isbns`] but requests are made according to `config`,
+    /// sharing one client between every ISBN.
+    pub async fn from_isbns_with_config(
+        config: &ReconConfig,
+        sources: &[Source],
+        isbns: &[Isbn],
+    ) -> Vec<(Isbn, Result<Metadata, ReconError>)> {
+        let client = match config.client() {
+            Ok(client) => client,
+            Err(err) => {
+                // errors cannot be cloned, the following ones are built again the same way
+                let mut err = Some(err);
+                return isbns
+                    .iter()
+                    .map(|isbn| {
+                        let err =
+                            err.take()
+                                .or_else(|| config.client().err())
+                                .unwrap_or_else(|| {
+                                    ReconError::Message("Cannot build the HTTP client".to_owned())
+                                });
+                        (isbn.clone(), Err(err))
+                    })
+                    .collect();
+            }
+        };
+
+        let client = &client;
+
+        stream::iter(isbns)
+            .map(|isbn| async move {
+                let metadata = Self::search_isbn(client, config, sources, isbn, None).await;
+                (isbn.clone(), metadata)
+            })
+            .buffered(config.max_concurrent_isbns.max(1))
+            .collect()
+            .await
+    }
+
+    /// Same as [`Metadata::from_isbn`] but requests are made according to `config`.
+    pub async fn from_isbn_with_config(
+        config: &ReconConfig,
+        sources: &[Source],
+        isbn: &Isbn,
+    ) -> Result<Metadata, ReconError> {
+        let client = config.client()?;
+
+        Self::search_isbn(&client, config, sources, isbn, None).await
+    }
+
+    /// Same as [`Metadata::from_isbn`] but every request is issued through `client`,
+    /// reusing its connection pool instead of building a new client per request.
+    pub async fn from_isbn_with_client(
+        client: &reqwest::Client,
+        sources: &[Source],
+        isbn: &Isbn,
+    ) -> Result<Metadata, ReconError> {
+        Self::search_isbn(client, &ReconConfig::default(), sources, isbn, None).await
+    }
+
+    /// Same as [`Metadata::from_isbn`] but errors from individual sources are returned
+    /// alongside the [`Metadata`] merged from the sources that succeeded.
+    /// Sources that have no book with this ISBN are not considered failed.
+    pub async fn from_isbn_with_errors(
+        sources: &[Source],
+        isbn: &Isbn,
+    ) -> (Metadata, Vec<(Source, ReconError)>) {
+        let config = ReconConfig::default();
+
+        match config.client() {
+            Ok(client) => {
+                let (metadata, mut errors) =
+                    Self::search_isbn_with_errors(&client, &config, sources, isbn, None).await;
+
+                errors.retain(|(_, err)| !matches!(err, ReconError::NotFound(_)));
+
+                (metadata, errors)
+            }
+            Err(err) => (
+                Metadata::default(),
+                sources
+                    .iter()
+                    .map(|s| (*s, ReconError::Message(err.to_string())))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Same as [`Metadata::from_isbn`] but results are kept separate for each source
+    /// instead of being merged, for attribution of every value to the source it came from.
+    /// Sources that failed or have no book with this ISBN are left out.
+    pub async fn from_isbn_per_source(
+        sources: &[Source],
+        isbn: &Isbn,
+    ) -> Result<HashMap<Source, Metadata>, ReconError> {
+        let config = ReconConfig::default();
+        let client = config.client()?;
 
         let futures_list = sources
             .iter()
-            .map(|s| Self::isbn_from_source(s, isbn))
+            .map(|s| Self::isbn_from_source(&client, &config, s, isbn))
             .collect::<Vec<_>>();
 
         let metadata_list = join_all(futures_list).await;
 
-        for m in metadata_list {
-            metadata = metadata + m?;
+        let mut per_source = HashMap::new();
+
+        for (source, m) in sources.iter().zip(metadata_list) {
+            match m {
+                Ok(mut m) => {
+                    m.normalize_isbns();
+                    per_source.insert(*source, m);
+                }
+                Err(err) => debug!("Skipping source {:?}: {}", source, err),
+            }
+        }
+
+        Ok(per_source)
+    }
+
+    /// Same as [`Metadata::from_isbn`] but returns as soon as any source finds the book,
+    /// cancelling requests to the other sources.
+    /// The result comes from a single source and is therefore not merged.
+    pub async fn from_isbn_race(sources: &[Source], isbn: &Isbn) -> Result<Metadata, ReconError> {
+        let config = ReconConfig::default();
+        let client = config.client()?;
+
+        let mut futures_list = sources
+            .iter()
+            .map(|s| Self::isbn_from_source(&client, &config, s, isbn))
+            .collect::<FuturesUnordered<_>>();
+
+        let mut error = None;
+
+        while let Some(m) = futures_list.next().await {
+            match m {
+                Ok(mut m) if !m.is_empty() => {
+                    m.normalize_isbns();
+                    return Ok(m);
+                }
+                Ok(_) => {}
+                // a source failing outright takes precedence over one finding nothing
+                Err(ReconError::NotFound(_)) if error.is_some() => {}
+                Err(err) => error = Some(err),
+            }
+        }
+
+        Err(error.unwrap_or_else(|| ReconError::NotFound(isbn.clone())))
+    }
+
+    /// Queries every source in parallel and merges the successful results.
+    /// Once `cancel` is triggered, sources yet to respond are left out.
+    async fn search_isbn_with_errors(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        sources: &[Source],
+        isbn: &Isbn,
+        cancel: Option<&CancellationToken>,
+    ) -> (Metadata, Vec<(Source, ReconError)>) {
+        let mut errors = Vec::new();
+
+        let mut pending = sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| async move {
+                let metadata = Self::isbn_from_source(client, config, source, isbn).await;
+                (index, source, metadata)
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        let mut metadata_list = Vec::with_capacity(sources.len());
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = cancelled(cancel) => break,
+                next = pending.next() => match next {
+                    Some(result) => metadata_list.push(result),
+                    None => break,
+                },
+            }
+        }
+
+        // in the order of `sources` rather than of completion
+        metadata_list.sort_by_key(|(index, ..)| *index);
+
+        let mut metadata = metadata_list
+            .into_iter()
+            .filter_map(|(_, source, m)| match m {
+                Ok(m) => Some(m),
+                Err(err) => {
+                    debug!("Skipping source {:?}: {}", source, err);
+                    errors.push((*source, err));
+                    None
+                }
+            })
+            .sum::<Metadata>();
+
+        metadata.normalize_isbns();
+
+        (metadata, errors)
+    }
+
+    /// Fails only if every source failed, with the error of the first source.
+    /// [`ReconError::NotFound`] is returned only if no source has a book with this ISBN.
+    async fn search_isbn(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        sources: &[Source],
+        isbn: &Isbn,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Metadata, ReconError> {
+        let (metadata, mut errors) =
+            Self::search_isbn_with_errors(client, config, sources, isbn, cancel).await;
+
+        if !sources.is_empty() && errors.len() == sources.len() {
+            // a source failing outright takes precedence over one finding nothing
+            let index = errors
+                .iter()
+                .position(|(_, err)| !matches!(err, ReconError::NotFound(_)))
+                .unwrap_or(0);
+
+            return Err(errors.swap_remove(index).1);
+        }
+
+        if metadata.is_empty() && cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(cancelled_error());
         }
 
         Ok(metadata)
@@ -177,33 +1068,321 @@ impl Metadata {
     /// Performs parallel search on ISBNs provided by first argument.
     /// Second argument describes sources to cross-examine.
     /// Returns a list of [`Metadata`] that matches description
-    /// provided by the third argument.
+    /// provided by the third argument, in the order `search` ranked them.
+    /// [`Metadata::rank_by_relevance`] orders them by closeness to the description instead.
     pub async fn from_description(
         search: &Source,
         sources: &[Source],
         description: &str,
     ) -> Result<Vec<Metadata>, ReconError> {
-        let isbns: Vec<Isbn> = Self::description_from_source(search, description).await?;
+        Self::from_description_with_config(&ReconConfig::default(), search, sources, description)
+            .await
+    }
 
-        let futures_list = isbns
-            .iter()
-            .map(|isbn| Self::from_isbn(sources, isbn))
-            .collect::<Vec<_>>();
+    /// Same as [`Metadata::from_description`] but requests are made according to `config`.
+    pub async fn from_description_with_config(
+        config: &ReconConfig,
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+    ) -> Result<Vec<Metadata>, ReconError> {
+        let client = config.client()?;
 
-        let metadata_list = join_all(futures_list).await;
+        Self::search_description(&client, config, search, sources, description)
+            .await
+            .map(|found| found.results)
+    }
+
+    /// Same as [`Metadata::from_description_with_config`], also returning the total number
+    /// of matching books for paginated listings.
+    pub async fn from_description_with_total(
+        config: &ReconConfig,
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+    ) -> Result<SearchResults, ReconError> {
+        let client = config.client()?;
+
+        Self::search_description(&client, config, search, sources, description).await
+    }
+
+    /// Same as [`Metadata::from_description`] but every request is issued through `client`,
+    /// reusing its connection pool across the whole fan-out.
+    pub async fn from_description_with_client(
+        client: &reqwest::Client,
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+    ) -> Result<Vec<Metadata>, ReconError> {
+        Self::search_description(
+            client,
+            &ReconConfig::default(),
+            search,
+            sources,
+            description,
+        )
+        .await
+        .map(|found| found.results)
+    }
+
+    /// Same as [`Metadata::from_description_with_config`] but every result is paired with
+    /// the ISBN found by the search that seeded it, to tell apart or annotate results
+    /// that merged into similar metadata.
+    pub async fn from_description_with_isbns(
+        config: &ReconConfig,
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+    ) -> Result<Vec<(Isbn, Metadata)>, ReconError> {
+        let client = config.client()?;
+
+        Self::search_description_with_isbns(&client, config, search, sources, description, None)
+            .await
+            .map(|(results, _)| results)
+    }
+
+    /// Same as [`Metadata::from_isbn_with_config`] but returns early once `cancel` is
+    /// triggered, with the merged results of the sources that responded until then.
+    /// Fails with [`ReconError::Message`] if none did.
+    ///
+    /// Requests in flight are dropped rather than awaited,
+    /// sources may still finish processing them.
+    pub async fn from_isbn_cancellable(
+        config: &ReconConfig,
+        sources: &[Source],
+        isbn: &Isbn,
+        cancel: &CancellationToken,
+    ) -> Result<Metadata, ReconError> {
+        let client = config.client()?;
+
+        Self::search_isbn(&client, config, sources, isbn, Some(cancel)).await
+    }
+
+    /// Same as [`Metadata::from_description_with_config`] but stops searching further
+    /// ISBNs once `cancel` is triggered, returning the results completed until then.
+    /// Fails with [`ReconError::Message`] if the search itself had not completed.
+    ///
+    /// Requests in flight are dropped rather than awaited,
+    /// sources may still finish processing them.
+    pub async fn from_description_cancellable(
+        config: &ReconConfig,
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<Metadata>, ReconError> {
+        let client = config.client()?;
+
+        let (results, _) = Self::search_description_with_isbns(
+            &client,
+            config,
+            search,
+            sources,
+            description,
+            Some(cancel),
+        )
+        .await?;
+
+        Ok(results.into_iter().map(|(_, metadata)| metadata).collect())
+    }
+
+    async fn search_description(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+    ) -> Result<SearchResults, ReconError> {
+        let (results, total) =
+            Self::search_description_with_isbns(client, config, search, sources, description, None)
+                .await?;
+
+        Ok(SearchResults {
+            total,
+            results: results.into_iter().map(|(_, metadata)| metadata).collect(),
+        })
+    }
+
+    /// Results of a descriptive search paired with their ISBN, and the total number
+    /// of matching books. Once `cancel` is triggered, ISBNs yet to be searched are left out.
+    async fn search_description_with_isbns(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        search: &Source,
+        sources: &[Source],
+        description: &str,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<(Vec<(Isbn, Metadata)>, usize), ReconError> {
+        let (isbns, total) = tokio::select! {
+            biased;
+            _ = cancelled(cancel) => return Err(cancelled_error()),
+            found = Self::description_from_source(client, config, search, description) => found?,
+        };
+
+        let mut results =
+            Self::search_isbns_with_isbns(client, config, sources, &isbns, cancel).await;
+
+        if let Some(language) = &config.preferred_language {
+            let language = crate::util::translater::normalize_language(language);
+            // stable, keeping the order of the search source within either group
+            results.sort_by_key(|(_, metadata)| !metadata.language.contains(&language));
+        }
 
-        Ok(metadata_list.into_iter().flatten().collect())
+        Ok((results, total.unwrap_or(isbns.len())))
+    }
+
+    /// Parses a record of `source` fetched through another HTTP client, without making
+    /// a request: a GoogleBooks `volumeInfo` object or an OpenLibrary book object,
+    /// the value of a bibkey such as `ISBN:9781534431003`.
+    /// Other sources have no JSON records and return [`ReconError::Message`].
+    /// To read back [`Metadata`] serialized by this crate, use [`Metadata::from_json`].
+    pub fn from_source_value(
+        source: &Source,
+        value: &serde_json::Value,
+    ) -> Result<Metadata, ReconError> {
+        crate::source::from_value(source, value)
+    }
+
+    /// Same as [`Metadata::from_source_value`] but the record is parsed from a JSON string
+    pub fn from_source_json(source: &Source, json: &str) -> Result<Metadata, ReconError> {
+        let value = serde_json::from_str(json).map_err(ReconError::JSONParse)?;
+
+        Self::from_source_value(source, &value)
+    }
+
+    /// Reads the metadata of a local EPUB from the Dublin Core fields of its package document,
+    /// without any network request. The cover image is its path within the EPUB.
+    ///
+    /// `path` is either an EPUB unpacked into a directory or an EPUB file, whose entries may be
+    /// stored or deflated.
+    #[cfg(feature = "epub")]
+    pub fn from_epub(path: &std::path::Path) -> Result<Metadata, ReconError> {
+        crate::source::epub::from_path(path)
+    }
+
+    /// Reads the metadata of a local PDF from its document information dictionary and
+    /// XMP packets, without any network request: title, author, keywords as tags and
+    /// any ISBN written in them.
+    ///
+    /// A PDF without usable metadata gives an empty [`Metadata`] rather than an error.
+    /// Information dictionaries inside compressed object streams cannot be read.
+    #[cfg(feature = "pdf")]
+    pub fn from_pdf(path: &std::path::Path) -> Result<Metadata, ReconError> {
+        crate::source::pdf::from_path(path)
+    }
+
+    /// Performs parallel search on ISBNs of books with matching `title` and `author`
+    /// provided by `search`, using the source's structured search where it has one.
+    /// Second argument describes sources to cross-examine.
+    pub async fn from_title_author(
+        search: &Source,
+        sources: &[Source],
+        title: &str,
+        author: &str,
+    ) -> Result<Vec<Metadata>, ReconError> {
+        Self::from_title_author_with_config(&ReconConfig::default(), search, sources, title, author)
+            .await
+    }
+
+    /// Same as [`Metadata::from_title_author`] but requests are made according to `config`.
+    pub async fn from_title_author_with_config(
+        config: &ReconConfig,
+        search: &Source,
+        sources: &[Source],
+        title: &str,
+        author: &str,
+    ) -> Result<Vec<Metadata>, ReconError> {
+        let client = config.client()?;
+
+        let isbns: Vec<Isbn> =
+            Self::title_author_from_source(&client, config, search, title, author).await?;
+
+        Ok(Self::search_isbns(&client, config, sources, &isbns).await)
+    }
+
+    /// Cross-examines `sources` for each of `isbns`, skipping ISBNs every source failed on
+    /// and books published outside of [`ReconConfig::year_range`].
+    async fn search_isbns(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        sources: &[Source],
+        isbns: &[Isbn],
+    ) -> Vec<Metadata> {
+        Self::search_isbns_with_isbns(client, config, sources, isbns, None)
+            .await
+            .into_iter()
+            .map(|(_, metadata)| metadata)
+            .collect()
+    }
+
+    /// Same as [`Metadata::search_isbns`], pairing every result with its ISBN.
+    /// Once `cancel` is triggered, ISBNs yet to be searched are left out.
+    async fn search_isbns_with_isbns(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        sources: &[Source],
+        isbns: &[Isbn],
+        cancel: Option<&CancellationToken>,
+    ) -> Vec<(Isbn, Metadata)> {
+        // `buffered` keeps results in the order the search source ranked them
+        let mut pending = stream::iter(dedup_isbns(isbns))
+            .map(|isbn| async move {
+                let metadata = Self::search_isbn(client, config, sources, isbn, None).await;
+                metadata.map(|metadata| (isbn.clone(), metadata))
+            })
+            .buffered(config.max_concurrent_isbns.max(1));
+
+        let mut metadata_list = Vec::new();
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = cancelled(cancel) => break,
+                next = pending.next() => match next {
+                    Some(result) => metadata_list.push(result),
+                    None => break,
+                },
+            }
+        }
+
+        metadata_list
+            .into_iter()
+            .flatten()
+            .filter(|(_, metadata)| match config.year_range {
+                Some(range) => metadata.published_within(range, !config.exclude_undated),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Returns `true` if any publication date falls within the inclusive year `range`,
+    /// or there is none and `keep_undated` is set.
+    fn published_within(&self, (from, to): (i32, i32), keep_undated: bool) -> bool {
+        match self.publication_date.is_empty() {
+            true => keep_undated,
+            false => self
+                .publication_date
+                .iter()
+                .any(|date| (from..=to).contains(&date.year())),
+        }
     }
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use log::info;
 
     fn init_logger() {
         let _ = env_logger::builder().is_test(true).try_init();
     }
 
+    /// Metadata holding only `title`, shared by the tests of other modules
+    pub(crate) fn titled(title: &str) -> super::Metadata {
+        let mut metadata = super::Metadata::default();
+        metadata.title.insert(title.to_owned());
+        metadata
+    }
+
     #[tokio::test]
     async fn parses_from_isbn() {
         use super::Metadata;
@@ -240,4 +1419,425 @@ mod test {
         info!("Response: {:#?}", res);
         assert!(res.is_ok());
     }
+
+    #[tokio::test]
+    async fn parses_from_title_author() {
+        use super::Metadata;
+        use crate::recon::{ReconError, Source};
+
+        init_logger();
+
+        let sources = [Source::GoogleBooks, Source::OpenLibrary];
+
+        let res: Result<Vec<Metadata>, ReconError> = Metadata::from_title_author(
+            &Source::OpenLibrary,
+            &sources,
+            "This is how you lose the time war",
+            "Max Gladstone",
+        )
+        .await;
+
+        info!("Response: {:#?}", res);
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn keeps_errors_of_failed_sources() {
+        use super::Metadata;
+        use crate::recon::{ReconError, Source};
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        init_logger();
+
+        // LibraryThing fails every request without a developer key, the default
+        let isbn = Isbn::from_str("9791090636071").unwrap();
+
+        let (metadata, errors) =
+            Metadata::from_isbn_with_errors(&[Source::LibraryThing], &isbn).await;
+
+        info!("Response: {:#?} {:#?}", metadata, errors);
+        assert!(metadata.title.is_empty());
+        assert!(matches!(
+            errors.as_slice(),
+            [(Source::LibraryThing, ReconError::Message(_))]
+        ));
+
+        let res = Metadata::from_isbn(&[Source::LibraryThing], &isbn).await;
+        assert!(res.is_err());
+
+        let per_source = Metadata::from_isbn_per_source(&[Source::LibraryThing], &isbn).await;
+        assert!(per_source.unwrap().is_empty());
+
+        let res = Metadata::from_isbn_race(&[Source::LibraryThing], &isbn).await;
+        assert!(matches!(res, Err(ReconError::Message(_))));
+
+        let res = Metadata::from_isbn_race(&[], &isbn).await;
+        assert!(matches!(res, Err(ReconError::NotFound(_))));
+
+        let isbns = [isbn.clone(), isbn.clone()];
+        let res = Metadata::from_isbns(&[Source::LibraryThing], &isbns).await;
+        assert_eq!(res.len(), 2);
+        assert!(res
+            .iter()
+            .all(|(i, m)| *i == isbn && matches!(m, Err(ReconError::Message(_)))));
+    }
+
+    #[tokio::test]
+    async fn keeps_client_errors_of_batches() {
+        use super::Metadata;
+        use crate::recon::{ReconConfig, ReconError, Source};
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        // not a valid header value, the client cannot be built
+        let config = ReconConfig::new().preferred_language("e\nn");
+        let isbns = [
+            Isbn::from_str("9781534431003").unwrap(),
+            Isbn::from_str("1534431004").unwrap(),
+        ];
+
+        let res = Metadata::from_isbns_with_config(&config, &[Source::GoogleBooks], &isbns).await;
+        assert_eq!(res.len(), 2);
+        assert!(res.iter().all(|(_, m)| matches!(
+            m,
+            Err(ReconError::Message(message)) if message.starts_with("Invalid language")
+        )));
+    }
+
+    #[test]
+    fn deserializes_serialized() {
+        use super::PubDate;
+        use super::{CoverImage, Metadata};
+        use chrono::NaiveDate;
+        use isbn2::{Isbn10, Isbn13};
+        use std::str::FromStr;
+
+        init_logger();
+
+        let mut metadata = Metadata::default();
+        metadata
+            .isbn10
+            .insert(Isbn10::from_str("1534431004").unwrap());
+        metadata
+            .isbn13
+            .insert(Isbn13::from_str("9781534431003").unwrap());
+        metadata
+            .title
+            .insert("This Is How You Lose the Time War".to_owned());
+        metadata.author.insert("Amal El-Mohtar".to_owned());
+        metadata.author.insert("Max Gladstone".to_owned());
+        metadata.page_count.insert(208);
+        metadata
+            .publication_date
+            .insert(PubDate::Full(NaiveDate::from_ymd_opt(2019, 7, 16).unwrap()));
+        metadata.cover_image.large.insert("c.jpg".to_owned());
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        info!("Serialized: {}", json);
+
+        let back: Metadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.isbn10, metadata.isbn10);
+        assert_eq!(back.isbn13, metadata.isbn13);
+        assert_eq!(back.title, metadata.title);
+        assert_eq!(back.author, metadata.author);
+        assert_eq!(back.page_count, metadata.page_count);
+        assert_eq!(back.publication_date, metadata.publication_date);
+        assert_eq!(back.cover_image, metadata.cover_image);
+
+        let partial: Metadata = serde_json::from_str(r#"{ "title": ["a"] }"#).unwrap();
+        assert!(partial.title.contains("a"));
+        assert_eq!(partial.cover_image, CoverImage::default());
+
+        assert!(serde_json::from_str::<Metadata>(r#"{ "isbn13": ["not an isbn"] }"#).is_err());
+    }
+
+    #[test]
+    fn picks_best_cover_url() {
+        use super::CoverImage;
+
+        let mut cover_image = CoverImage::default();
+        assert_eq!(cover_image.best_url(), None);
+
+        cover_image.thumbnail.insert("thumbnail".to_owned());
+        cover_image.medium.insert("medium".to_owned());
+        cover_image.small.insert("small".to_owned());
+
+        assert_eq!(cover_image.best_url(), Some("medium"));
+        assert_eq!(
+            cover_image.iter().collect::<Vec<_>>(),
+            vec!["medium", "small", "thumbnail"]
+        );
+
+        let sizes = cover_image
+            .sizes()
+            .filter(|(_, images)| !images.is_empty())
+            .map(|(size, _)| size)
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, vec!["medium", "small", "thumbnail"]);
+    }
+
+    #[test]
+    fn dedups_cover_urls_by_base() {
+        use super::CoverImage;
+
+        let url = |zoom: u8| {
+            format!(
+                "http://books.google.com/books/content?id=cMfxDwAAQBAJ&printsec=frontcover&img=1&zoom={}&edge=curl",
+                zoom
+            )
+        };
+
+        let mut cover_image = CoverImage::default();
+        cover_image.large.insert(url(4));
+        cover_image.medium.insert(url(3));
+        cover_image.thumbnail.insert(url(1));
+        cover_image
+            .small_thumbnail
+            .insert("https://covers.openlibrary.org/b/id/9383993-S.jpg".to_owned());
+
+        cover_image.dedup_by_base();
+
+        assert!(cover_image.large.contains(&url(4)));
+        assert!(cover_image.medium.is_empty());
+        assert!(cover_image.thumbnail.is_empty());
+        assert_eq!(cover_image.small_thumbnail.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn refuses_to_download_missing_cover() {
+        use super::CoverImage;
+        use crate::recon::ReconError;
+
+        let res = CoverImage::default()
+            .download_largest(&reqwest::Client::new())
+            .await;
+        assert!(matches!(res, Err(ReconError::MissingField(_))));
+    }
+
+    #[tokio::test]
+    async fn parses_isbn_str() {
+        use super::Metadata;
+        use crate::recon::ReconError;
+
+        assert!(Metadata::from_isbn_str(&[], " 978-1-5344-3100-3 ")
+            .await
+            .is_ok());
+        assert!(matches!(
+            Metadata::from_isbn_str(&[], "978-1-5344-3100-4").await,
+            Err(ReconError::ISBNParse(_))
+        ));
+    }
+
+    #[test]
+    fn dedups_isbns() {
+        use super::dedup_isbns;
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        let isbns = [
+            "9780765326355",
+            "9781534431003",
+            "0765326353",
+            "9780765326355",
+        ]
+        .iter()
+        .map(|isbn| Isbn::from_str(isbn).unwrap())
+        .collect::<Vec<_>>();
+
+        assert_eq!(dedup_isbns(&isbns), vec![&isbns[0], &isbns[1]]);
+    }
+
+    #[test]
+    fn sums_metadata() {
+        use super::Metadata;
+
+        let summed = vec![titled("Dry"), titled("Scythe"), titled("Dry")]
+            .into_iter()
+            .sum::<Metadata>();
+        assert_eq!(summed.title.len(), 2);
+
+        let collected = vec![titled("Dry"), titled("Scythe")]
+            .into_iter()
+            .collect::<Metadata>();
+        assert_eq!(collected, summed);
+        assert_eq!(titled("Dry") + titled("Scythe"), collected);
+
+        assert!(std::iter::empty::<Metadata>().sum::<Metadata>().is_empty());
+    }
+
+    #[test]
+    fn filters_by_publication_year() {
+        use super::Metadata;
+        use super::PubDate;
+        use chrono::NaiveDate;
+
+        let mut metadata = Metadata::default();
+        assert!(metadata.published_within((2000, 2010), true));
+        assert!(!metadata.published_within((2000, 2010), false));
+
+        metadata
+            .publication_date
+            .insert(PubDate::Full(NaiveDate::from_ymd_opt(1995, 1, 1).unwrap()));
+        metadata
+            .publication_date
+            .insert(PubDate::Full(NaiveDate::from_ymd_opt(2019, 7, 16).unwrap()));
+        assert!(metadata.published_within((2019, 2019), false));
+        assert!(!metadata.published_within((2000, 2010), true));
+    }
+
+    #[test]
+    fn ranks_by_relevance() {
+        use super::Metadata;
+
+        let book = |title: &str, author: &str| {
+            let mut metadata = Metadata::default();
+            metadata.title.insert(title.to_owned());
+            metadata.author.insert(author.to_owned());
+            metadata
+        };
+
+        let time_war = book("This Is How You Lose the Time War", "Amal El-Mohtar");
+        assert_eq!(time_war.relevance("time war el-mohtar"), 1.0);
+        assert_eq!(time_war.relevance("Time, Space"), 0.5);
+        assert_eq!(time_war.relevance(""), 0.0);
+
+        let ranked = Metadata::rank_by_relevance(
+            vec![
+                book("Dry", "Neal Shusterman"),
+                book("Scythe", "Neal Shusterman"),
+                time_war,
+            ],
+            "Lose the Time War",
+        );
+        let titles = ranked
+            .iter()
+            .map(|(_, metadata)| metadata.title.iter().next().unwrap().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            ["This Is How You Lose the Time War", "Dry", "Scythe"]
+        );
+    }
+
+    #[test]
+    fn keeps_publication_date_precision() {
+        use super::PubDate;
+        use chrono::NaiveDate;
+        use std::str::FromStr;
+
+        let full = PubDate::Full(NaiveDate::from_ymd_opt(2011, 3, 15).unwrap());
+
+        for (date, s) in [
+            (PubDate::Year(2011), "2011"),
+            (PubDate::YearMonth(2011, 3), "2011-03"),
+            (full, "2011-03-15"),
+        ] {
+            assert_eq!(date.to_string(), s);
+            assert_eq!(PubDate::from_str(s).unwrap(), date);
+            assert_eq!(serde_json::to_value(date).unwrap(), s);
+        }
+
+        assert!(PubDate::from_str("2011-13").is_err());
+        assert!(PubDate::Year(2011) < PubDate::YearMonth(2011, 1));
+        assert!(PubDate::YearMonth(2011, 3) < full);
+        assert!(full < PubDate::YearMonth(2011, 4));
+
+        let first = PubDate::Full(NaiveDate::from_ymd_opt(2011, 3, 1).unwrap());
+        assert!(PubDate::YearMonth(2011, 3) < first);
+
+        let dates = [
+            PubDate::Year(2011),
+            PubDate::YearMonth(2011, 1),
+            PubDate::YearMonth(2011, 3),
+            PubDate::Full(NaiveDate::from_ymd_opt(2011, 1, 1).unwrap()),
+            first,
+            full,
+        ];
+        for a in &dates {
+            for b in &dates {
+                assert_eq!(a.cmp(b) == std::cmp::Ordering::Equal, a == b);
+            }
+        }
+    }
+
+    #[test]
+    fn merges_similar_titles() {
+        use super::Metadata;
+
+        let book = |title: &str, publisher: &str| {
+            let mut metadata = Metadata::default();
+            metadata.title.insert(title.to_owned());
+            metadata.publisher.insert(publisher.to_owned());
+            metadata
+        };
+
+        let merged = Metadata::merge_all(vec![
+            book("The Way of Kings", "Tor Books"),
+            book("The Way of Kings (Stormlight Archive, #1)", "TOR BOOKS"),
+            book("The Way of Kings: Part One", "Tor"),
+        ]);
+
+        assert_eq!(merged.title.len(), 2);
+        assert!(merged
+            .title
+            .contains("The Way of Kings (Stormlight Archive, #1)"));
+        assert!(merged.title.contains("The Way of Kings: Part One"));
+        assert_eq!(merged.publisher.len(), 2);
+
+        let merged = Metadata::merge_all_with_threshold(
+            vec![
+                book("The Way of Kings", "Tor Books"),
+                book("The Way of Kings: Part One", "Tor Books"),
+            ],
+            0.5,
+        );
+        assert_eq!(merged.title.len(), 1);
+    }
+
+    #[test]
+    fn reports_empty() {
+        use super::Metadata;
+
+        let mut metadata = Metadata::default();
+        assert!(metadata.is_empty());
+
+        metadata
+            .cover_image
+            .small
+            .insert("https://covers.openlibrary.org/b/id/9383993-S.jpg".to_owned());
+        assert!(!metadata.cover_image.is_empty());
+        assert!(!metadata.is_empty());
+    }
+
+    #[test]
+    fn normalizes_isbns() {
+        use super::Metadata;
+        use isbn2::{Isbn10, Isbn13};
+        use std::str::FromStr;
+
+        init_logger();
+
+        let mut metadata = Metadata::default();
+        metadata
+            .isbn10
+            .insert(Isbn10::from_str("1534431004").unwrap());
+        metadata
+            .isbn13
+            .insert(Isbn13::from_str("9780765326355").unwrap());
+        metadata
+            .isbn13
+            .insert(Isbn13::from_str("9791090636071").unwrap());
+
+        metadata.normalize_isbns();
+
+        assert_eq!(metadata.isbn10.len(), 2);
+        assert!(metadata
+            .isbn10
+            .contains(&Isbn10::from_str("0765326353").unwrap()));
+        assert_eq!(metadata.isbn13.len(), 3);
+        assert!(metadata
+            .isbn13
+            .contains(&Isbn13::from_str("9781534431003").unwrap()));
+    }
 }
@@ -1,4 +1,5 @@
-use std::{error, fmt};
+use crate::metadata::Metadata;
+use std::{collections::HashMap, error, fmt, str::FromStr, time::Duration};
 
 /// A list of database or search providers.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -7,10 +8,84 @@ pub enum Source {
     GoogleBooks,
     /// OpenLibrary API at <https://openlibrary.org/developers/api>
     OpenLibrary,
-    /// TBD
+    /// Goodreads web pages at <https://www.goodreads.com>
     Goodreads,
-    /// TBD
+    /// Amazon product pages at <https://www.amazon.com>
     Amazon,
+    /// LibraryThing web services at <https://www.librarything.com/services/webservices.php>,
+    /// never part of [`Source::all`] as it requires [`ReconConfig::library_thing_api_key`]
+    LibraryThing,
+    /// Crossref REST API at <https://api.crossref.org>, never part of [`Source::all`]
+    /// as it mostly indexes academic books
+    Crossref,
+    /// Fixtures registered in [`ReconConfig::mock`], never part of [`Source::all`]
+    #[cfg(feature = "mock")]
+    Mock,
+}
+
+impl Source {
+    /// Every implemented source, for searching all of them
+    pub fn all() -> &'static [Source] {
+        &[
+            Source::GoogleBooks,
+            Source::OpenLibrary,
+            Source::Goodreads,
+            Source::Amazon,
+        ]
+    }
+
+    /// Public URL requests to the source are made under, without a trailing slash.
+    /// Overridden per source by [`ReconConfig::base_urls`].
+    pub fn api_base_url(&self) -> &'static str {
+        match self {
+            Source::GoogleBooks => "https://www.googleapis.com/books/v1",
+            Source::OpenLibrary => "https://openlibrary.org",
+            Source::Goodreads => "https://www.goodreads.com",
+            Source::Amazon => "https://www.amazon.com",
+            Source::LibraryThing => "https://www.librarything.com/services/rest/1.1",
+            Source::Crossref => "https://api.crossref.org",
+            // fixtures are served without requests
+            #[cfg(feature = "mock")]
+            Source::Mock => "",
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Source::GoogleBooks => "google_books",
+            Source::OpenLibrary => "open_library",
+            Source::Goodreads => "goodreads",
+            Source::Amazon => "amazon",
+            Source::LibraryThing => "library_thing",
+            Source::Crossref => "crossref",
+            #[cfg(feature = "mock")]
+            Source::Mock => "mock",
+        };
+
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Source {
+    type Err = ReconError;
+
+    /// Parses the name of a source case-insensitively, with or without an underscore
+    /// between words, e.g. `google_books`, `GoogleBooks` or `openlibrary`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "google_books" | "googlebooks" => Ok(Source::GoogleBooks),
+            "open_library" | "openlibrary" => Ok(Source::OpenLibrary),
+            "goodreads" => Ok(Source::Goodreads),
+            "amazon" => Ok(Source::Amazon),
+            "library_thing" | "librarything" => Ok(Source::LibraryThing),
+            "crossref" => Ok(Source::Crossref),
+            #[cfg(feature = "mock")]
+            "mock" => Ok(Source::Mock),
+            _ => Err(ReconError::Message(format!("Unknown source: {}", s))),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -24,6 +99,9 @@ pub enum ReconError {
     /// A wrapper around [`reqwest::Error`]
     /// typically raised by `reqwest::get(url)`
     Connection(reqwest::Error),
+    /// A wrapper around [`reqwest::Error`]
+    /// raised when a request takes longer than [`ReconConfig::timeout`]
+    Timeout(reqwest::Error),
     /// A wrapper around [`isbn2::IsbnError`]
     /// typically raised by `isbn2::Isbn::from_str(possible_isbn_str)`
     ISBNParse(isbn2::IsbnError),
@@ -32,6 +110,17 @@ pub enum ReconError {
     DateParse(chrono::ParseError),
     /// Missing field error
     MissingField(String),
+    /// The source has no book with this ISBN
+    NotFound(isbn2::Isbn),
+    /// The source kept rejecting requests with `429 Too Many Requests`
+    /// or `503 Service Unavailable` after every attempt
+    RateLimited {
+        /// Delay requested by the source's `Retry-After` header, if any
+        retry_after: Option<Duration>,
+    },
+    /// A wrapper around [`std::io::Error`]
+    /// raised reading local files, see `Metadata::from_epub` and `Metadata::from_pdf`
+    Io(std::io::Error),
 }
 
 impl fmt::Display for ReconError {
@@ -40,4 +129,490 @@ impl fmt::Display for ReconError {
     }
 }
 
-impl error::Error for ReconError {}
+impl error::Error for ReconError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ReconError::JSONParse(err) => Some(err),
+            ReconError::Connection(err) | ReconError::Timeout(err) => Some(err),
+            ReconError::DateParse(err) => Some(err),
+            ReconError::Io(err) => Some(err),
+            // `isbn2::IsbnError` does not implement `std::error::Error`
+            ReconError::ISBNParse(_) => None,
+            ReconError::Message(_)
+            | ReconError::MissingField(_)
+            | ReconError::NotFound(_)
+            | ReconError::RateLimited { .. } => None,
+        }
+    }
+}
+
+impl ReconError {
+    /// Wraps a [`reqwest::Error`] into [`ReconError::Timeout`] if the request timed out
+    /// and [`ReconError::Connection`] otherwise.
+    pub(crate) fn connection(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            ReconError::Timeout(err)
+        } else {
+            ReconError::Connection(err)
+        }
+    }
+}
+
+/// Configuration for requests made to [`Source`]s.
+///
+/// Fields can be set directly or through the builder methods, and the configuration
+/// searches its [`ReconConfig::sources`] itself:
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use isbn2::Isbn;
+/// use recon_metadata::{ReconConfig, Source};
+/// use std::str::FromStr;
+/// use std::time::Duration;
+///
+/// let config = ReconConfig::new()
+///     .sources(&[Source::GoogleBooks, Source::OpenLibrary])
+///     .timeout(Duration::from_secs(10))
+///     .limit(5);
+///
+/// let isbn = Isbn::from_str("9781534431003").unwrap();
+/// let metadata = config.from_isbn(&isbn).await;
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReconConfig {
+    /// Sources searched by [`ReconConfig::from_isbn`] and [`ReconConfig::from_description`],
+    /// the first one also provides the ISBNs of descriptive searches.
+    /// Defaults to [`Source::all`].
+    pub sources: Vec<Source>,
+    /// Time limit for each request, from connecting until the response body is read.
+    /// Defaults to 30 seconds.
+    pub timeout: Duration,
+    /// Number of times a request is attempted before giving up on connection errors
+    /// and `5xx` responses. `1` disables retrying. Defaults to 3.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every following retry.
+    /// Defaults to 500 milliseconds.
+    pub retry_base_delay: Duration,
+    /// Longest delay a `Retry-After` header is waited for before retrying, requests asking
+    /// for more fail right away with [`ReconError::RateLimited`]. Defaults to 30 seconds.
+    pub max_retry_after: Duration,
+    /// GoogleBooks API key, anonymous requests are made if `None`.
+    /// <https://developers.google.com/books/docs/v1/using#APIKey>
+    pub google_books_api_key: Option<String>,
+    /// Two letter country code sent with GoogleBooks requests,
+    /// some regions receive no results without one. Defaults to `US`.
+    pub google_books_country: Option<String>,
+    /// Proxy every request to every source goes through, e.g.
+    /// `reqwest::Proxy::all("http://proxy.example.com:8080")`. HTTP and HTTPS proxies are
+    /// supported, SOCKS proxies need the `socks` feature of `reqwest`.
+    /// Defaults to `None`, using the proxies of the `HTTP_PROXY`/`HTTPS_PROXY` environment.
+    pub proxy: Option<reqwest::Proxy>,
+    /// Base URLs replacing [`Source::api_base_url`] for some sources,
+    /// e.g. an internal mirror of OpenLibrary or a local test server. Defaults to none.
+    pub base_urls: HashMap<Source, String>,
+    /// Base URL of the OpenLibrary Covers API that cover URLs are built under, e.g. the covers
+    /// host of an OpenLibrary mirror set in [`ReconConfig::base_urls`].
+    /// Defaults to `https://covers.openlibrary.org`.
+    pub open_library_covers_url: String,
+    /// Two letter language code, e.g. `de`, sent as the `Accept-Language` header and used
+    /// to restrict GoogleBooks and bias OpenLibrary descriptive searches. Results in this
+    /// language are ranked first by [`ReconConfig::from_description`]. Defaults to `None`.
+    pub preferred_language: Option<String>,
+    /// LibraryThing developer key, required by [`Source::LibraryThing`].
+    /// <https://www.librarything.com/services/keys.php>
+    pub library_thing_api_key: Option<String>,
+    /// Number of results each source keeps from a descriptive search. Defaults to 3.
+    pub description_limit: usize,
+    /// Number of results skipped by descriptive searches, combined with
+    /// [`ReconConfig::description_limit`] to fetch later pages. Defaults to 0.
+    pub description_offset: usize,
+    /// Number of ISBNs found by a descriptive search that are looked up in the sources
+    /// at the same time. Defaults to 4.
+    pub max_concurrent_isbns: usize,
+    /// Makes extra requests for descriptions of OpenLibrary books, which are kept on
+    /// the work an edition belongs to rather than the edition itself. Defaults to `true`.
+    pub open_library_descriptions: bool,
+    /// Keeps only results of descriptive searches published within these years, inclusive.
+    /// Defaults to `None`, keeping every result.
+    pub year_range: Option<(i32, i32)>,
+    /// Also drops results with no known publication date when [`ReconConfig::year_range`]
+    /// is set. Defaults to `false`.
+    pub exclude_undated: bool,
+    /// `chrono` formats of full publication dates tried after the built-in ones,
+    /// e.g. `%d.%m.%Y` for sources in other locales. Defaults to none.
+    pub date_formats: Vec<String>,
+    /// Keeps author names given as `Last, First` in that order rather than turning them
+    /// into `First Last`. Defaults to `false`.
+    pub keep_last_first: bool,
+    /// `User-Agent` header sent with every request, some sources block requests without one.
+    /// Defaults to `recon_metadata/<version>`.
+    pub user_agent: String,
+    /// Skips sources failing every request for a while, disabled if `None`.
+    pub circuit_breaker: Option<crate::circuit_breaker::CircuitBreaker>,
+    /// Merges concurrent lookups of the same ISBN from the same source into one request,
+    /// disabled if `None`.
+    pub single_flight: Option<crate::single_flight::SingleFlight>,
+    /// Cache consulted before and populated after each source lookup, disabled if `None`.
+    #[cfg(feature = "cache")]
+    pub cache: Option<crate::cache::Cache>,
+    /// Cache on disk consulted after [`ReconConfig::cache`], disabled if `None`.
+    #[cfg(feature = "disk_cache")]
+    pub disk_cache: Option<crate::disk_cache::DiskCache>,
+    /// Fixtures served by [`Source::Mock`].
+    #[cfg(feature = "mock")]
+    pub mock: crate::source::mock::Mock,
+}
+
+impl Default for ReconConfig {
+    fn default() -> Self {
+        ReconConfig {
+            sources: Source::all().to_vec(),
+            timeout: Duration::from_secs(30),
+            max_attempts: 3,
+            retry_base_delay: Duration::from_millis(500),
+            max_retry_after: Duration::from_secs(30),
+            google_books_api_key: None,
+            google_books_country: Some("US".to_owned()),
+            proxy: None,
+            base_urls: HashMap::new(),
+            open_library_covers_url: "https://covers.openlibrary.org".to_owned(),
+            preferred_language: None,
+            library_thing_api_key: None,
+            description_limit: 3,
+            description_offset: 0,
+            max_concurrent_isbns: 4,
+            open_library_descriptions: true,
+            year_range: None,
+            exclude_undated: false,
+            date_formats: Vec::new(),
+            keep_last_first: false,
+            user_agent: concat!("recon_metadata/", env!("CARGO_PKG_VERSION")).to_owned(),
+            circuit_breaker: None,
+            single_flight: None,
+            #[cfg(feature = "cache")]
+            cache: None,
+            #[cfg(feature = "disk_cache")]
+            disk_cache: None,
+            #[cfg(feature = "mock")]
+            mock: Default::default(),
+        }
+    }
+}
+
+impl ReconConfig {
+    /// Default configuration, to be adjusted with the builder methods
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets [`ReconConfig::sources`]
+    pub fn sources(mut self, sources: &[Source]) -> Self {
+        self.sources = sources.to_vec();
+        self
+    }
+
+    /// Sets [`ReconConfig::timeout`]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets [`ReconConfig::max_attempts`]
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets [`ReconConfig::retry_base_delay`]
+    pub fn retry_base_delay(mut self, retry_base_delay: Duration) -> Self {
+        self.retry_base_delay = retry_base_delay;
+        self
+    }
+
+    /// Sets [`ReconConfig::max_retry_after`]
+    pub fn max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    /// Sets [`ReconConfig::google_books_api_key`]
+    pub fn google_api_key(mut self, key: impl Into<String>) -> Self {
+        self.google_books_api_key = Some(key.into());
+        self
+    }
+
+    /// Sets [`ReconConfig::proxy`]
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Overrides the base URL of `source` in [`ReconConfig::base_urls`]
+    pub fn base_url(mut self, source: Source, url: impl Into<String>) -> Self {
+        self.base_urls.insert(source, url.into());
+        self
+    }
+
+    /// Sets [`ReconConfig::open_library_covers_url`]
+    pub fn open_library_covers_url(mut self, url: impl Into<String>) -> Self {
+        self.open_library_covers_url = url.into();
+        self
+    }
+
+    /// Base URL requests to `source` are made under, [`ReconConfig::base_urls`] if set
+    /// and [`Source::api_base_url`] otherwise, without a trailing slash.
+    pub fn api_base_url(&self, source: &Source) -> &str {
+        match self.base_urls.get(source) {
+            Some(url) => url.trim_end_matches('/'),
+            None => source.api_base_url(),
+        }
+    }
+
+    /// Sets [`ReconConfig::preferred_language`]
+    pub fn preferred_language(mut self, language: impl Into<String>) -> Self {
+        self.preferred_language = Some(language.into());
+        self
+    }
+
+    /// Sets [`ReconConfig::library_thing_api_key`]
+    pub fn library_thing_api_key(mut self, key: impl Into<String>) -> Self {
+        self.library_thing_api_key = Some(key.into());
+        self
+    }
+
+    /// Sets [`ReconConfig::google_books_country`]
+    pub fn google_books_country(mut self, country: impl Into<String>) -> Self {
+        self.google_books_country = Some(country.into());
+        self
+    }
+
+    /// Sets [`ReconConfig::description_limit`]
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.description_limit = limit;
+        self
+    }
+
+    /// Sets [`ReconConfig::description_offset`]
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.description_offset = offset;
+        self
+    }
+
+    /// Sets [`ReconConfig::max_concurrent_isbns`]
+    pub fn max_concurrent_isbns(mut self, max_concurrent_isbns: usize) -> Self {
+        self.max_concurrent_isbns = max_concurrent_isbns;
+        self
+    }
+
+    /// Sets [`ReconConfig::open_library_descriptions`]
+    pub fn open_library_descriptions(mut self, enabled: bool) -> Self {
+        self.open_library_descriptions = enabled;
+        self
+    }
+
+    /// Sets [`ReconConfig::year_range`]
+    pub fn year_range(mut self, from: i32, to: i32) -> Self {
+        self.year_range = Some((from, to));
+        self
+    }
+
+    /// Sets [`ReconConfig::exclude_undated`]
+    pub fn exclude_undated(mut self, exclude_undated: bool) -> Self {
+        self.exclude_undated = exclude_undated;
+        self
+    }
+
+    /// Adds a format to [`ReconConfig::date_formats`]
+    pub fn date_format(mut self, format: impl Into<String>) -> Self {
+        self.date_formats.push(format.into());
+        self
+    }
+
+    /// Sets [`ReconConfig::keep_last_first`]
+    pub fn keep_last_first(mut self, keep_last_first: bool) -> Self {
+        self.keep_last_first = keep_last_first;
+        self
+    }
+
+    /// Sets [`ReconConfig::user_agent`]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Sets [`ReconConfig::circuit_breaker`]
+    pub fn circuit_breaker(
+        mut self,
+        circuit_breaker: crate::circuit_breaker::CircuitBreaker,
+    ) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Sets [`ReconConfig::single_flight`]
+    pub fn single_flight(mut self, single_flight: crate::single_flight::SingleFlight) -> Self {
+        self.single_flight = Some(single_flight);
+        self
+    }
+
+    /// Sets [`ReconConfig::cache`]
+    #[cfg(feature = "cache")]
+    pub fn cache(mut self, cache: crate::cache::Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Sets [`ReconConfig::disk_cache`]
+    #[cfg(feature = "disk_cache")]
+    pub fn disk_cache(mut self, disk_cache: crate::disk_cache::DiskCache) -> Self {
+        self.disk_cache = Some(disk_cache);
+        self
+    }
+
+    /// Sets [`ReconConfig::mock`]
+    #[cfg(feature = "mock")]
+    pub fn mock(mut self, mock: crate::source::mock::Mock) -> Self {
+        self.mock = mock;
+        self
+    }
+
+    /// Same as [`Metadata::from_isbn`] searching [`ReconConfig::sources`]
+    /// according to this configuration.
+    #[allow(clippy::wrong_self_convention)]
+    pub async fn from_isbn(&self, isbn: &isbn2::Isbn) -> Result<Metadata, ReconError> {
+        Metadata::from_isbn_with_config(self, &self.sources, isbn).await
+    }
+
+    /// Same as [`Metadata::from_description`] searching the first of [`ReconConfig::sources`]
+    /// for ISBNs and cross-examining all of them according to this configuration.
+    #[allow(clippy::wrong_self_convention)]
+    pub async fn from_description(&self, description: &str) -> Result<Vec<Metadata>, ReconError> {
+        let search = self
+            .sources
+            .first()
+            .ok_or_else(|| ReconError::Message("No source to search.".to_owned()))?;
+
+        Metadata::from_description_with_config(self, search, &self.sources, description).await
+    }
+
+    /// Builds a [`reqwest::Client`] according to this configuration.
+    pub fn client(&self) -> Result<reqwest::Client, ReconError> {
+        let mut headers = reqwest::header::HeaderMap::new();
+
+        if let Some(language) = &self.preferred_language {
+            // other languages are still accepted, only with a lower weight
+            let accept_language = format!("{}, *;q=0.5", language);
+            let value = reqwest::header::HeaderValue::from_str(&accept_language)
+                .map_err(|_| ReconError::Message(format!("Invalid language: {}", language)))?;
+            headers.insert(reqwest::header::ACCEPT_LANGUAGE, value);
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .user_agent(&self.user_agent)
+            .default_headers(headers);
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+
+        builder.build().map_err(ReconError::Connection)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn parses_displayed_source() {
+        use super::Source;
+        use std::str::FromStr;
+
+        for source in Source::all() {
+            assert_eq!(Source::from_str(&source.to_string()).unwrap(), *source);
+        }
+
+        assert_eq!(
+            Source::from_str("GoogleBooks").unwrap(),
+            Source::GoogleBooks
+        );
+        assert_eq!(
+            Source::from_str("OPEN_LIBRARY").unwrap(),
+            Source::OpenLibrary
+        );
+        assert_eq!(
+            Source::from_str("library_thing").unwrap(),
+            Source::LibraryThing
+        );
+        assert_eq!(Source::from_str("Crossref").unwrap(), Source::Crossref);
+        assert!(Source::from_str("library of babel").is_err());
+    }
+
+    #[test]
+    fn identifies_user_agent() {
+        use super::ReconConfig;
+
+        let config = ReconConfig::default();
+        assert!(config.user_agent.starts_with("recon_metadata/"));
+        assert!(config.client().is_ok());
+    }
+
+    #[test]
+    fn builds_config() {
+        use super::{ReconConfig, Source};
+        use std::time::Duration;
+
+        let config = ReconConfig::new()
+            .sources(&[Source::OpenLibrary])
+            .timeout(Duration::from_secs(5))
+            .limit(10)
+            .offset(20)
+            .google_api_key("key")
+            .preferred_language("de");
+
+        assert_eq!(config.sources, [Source::OpenLibrary]);
+        assert_eq!(config.timeout, Duration::from_secs(5));
+        assert_eq!(config.description_limit, 10);
+        assert_eq!(config.description_offset, 20);
+        assert_eq!(config.google_books_api_key.as_deref(), Some("key"));
+        assert_eq!(config.preferred_language.as_deref(), Some("de"));
+        assert_eq!(
+            config.api_base_url(&Source::GoogleBooks),
+            "https://www.googleapis.com/books/v1"
+        );
+
+        let config = config.base_url(Source::OpenLibrary, "http://127.0.0.1:8080/");
+        assert_eq!(
+            config.api_base_url(&Source::OpenLibrary),
+            "http://127.0.0.1:8080"
+        );
+        assert!(config.client().is_ok());
+        assert_eq!(ReconConfig::new().sources, Source::all());
+    }
+
+    #[tokio::test]
+    async fn refuses_description_search_without_sources() {
+        use super::{ReconConfig, ReconError};
+
+        let res = ReconConfig::new()
+            .sources(&[])
+            .from_description("Dry")
+            .await;
+        assert!(matches!(res, Err(ReconError::Message(_))));
+    }
+
+    #[test]
+    fn exposes_error_source() {
+        use super::ReconError;
+        use chrono::NaiveDate;
+        use std::error::Error;
+
+        let err = NaiveDate::parse_from_str("not a date", "%Y-%m-%d").unwrap_err();
+        let err = ReconError::DateParse(err);
+        assert!(err.source().is_some());
+
+        let err = ReconError::Message("message".to_owned());
+        assert!(err.source().is_none());
+    }
+}
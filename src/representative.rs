@@ -0,0 +1,339 @@
+use crate::metadata::Metadata;
+use crate::metadata::PubDate;
+use crate::recon::Source;
+use isbn2::{Isbn10, Isbn13};
+use std::collections::HashMap;
+
+/// A single value for each field of [`Metadata`], see [`Metadata::representative`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MetadataOne {
+    /// Lowest ISBN10
+    pub isbn10:           Option<Isbn10>,
+    /// Lowest ISBN13
+    pub isbn13:           Option<Isbn13>,
+    /// Longest title
+    pub title:            Option<String>,
+    /// Longest subtitle
+    pub subtitle:         Option<String>,
+    /// Most common series
+    pub series:           Option<String>,
+    /// Every author in sorted order, as co-authors would be lost by picking one
+    pub author:           Vec<String>,
+    /// Longest description
+    pub description:      Option<String>,
+    /// Page count most sources agree on, see [`Metadata::page_count_representative`]
+    pub page_count:       Option<u16>,
+    /// Most common publisher
+    pub publisher:        Option<String>,
+    /// Earliest publication date
+    pub publication_date: Option<PubDate>,
+    /// Lowest language code
+    pub language:         Option<String>,
+    /// Every tag in sorted order
+    pub tag:              Vec<String>,
+    /// URL of the largest cover image, see [`CoverImage::best_url`](crate::CoverImage::best_url)
+    pub cover_image:      Option<String>,
+    /// Highest average rating
+    pub average_rating:   Option<String>,
+    /// Highest ratings count
+    pub ratings_count:    Option<u32>,
+}
+
+/// Priorities of sources used by [`Metadata::representative_by_priority`],
+/// higher priorities win and sources without one have a priority of `0`.
+///
+/// Priorities can be overridden for individual fields, named as in [`MetadataOne`]:
+///
+/// ```
+/// use recon_metadata::{representative::SourcePriority, Source};
+/// use std::collections::HashMap;
+///
+/// // prefer Goodreads titles but GoogleBooks descriptions
+/// let priority = SourcePriority::new(HashMap::from([(Source::Goodreads, 1)]))
+///     .field("description", HashMap::from([(Source::GoogleBooks, 1)]));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SourcePriority {
+    default: HashMap<Source, u8>,
+    fields:  HashMap<String, HashMap<Source, u8>>,
+}
+
+impl SourcePriority {
+    /// Uses `priority` for every field without its own
+    pub fn new(priority: HashMap<Source, u8>) -> Self {
+        SourcePriority {
+            default: priority,
+            fields:  HashMap::new(),
+        }
+    }
+
+    /// Uses `priority` for `field` only
+    pub fn field(mut self, field: &str, priority: HashMap<Source, u8>) -> Self {
+        self.fields.insert(field.to_owned(), priority);
+        self
+    }
+
+    /// Orders `values` by descending priority for `field`,
+    /// ties are broken by the order [`Source`] declares its variants in
+    fn ordered<'a, T>(&self, field: &str, values: &'a [(Source, T)]) -> Vec<&'a T> {
+        let priority = self.fields.get(field).unwrap_or(&self.default);
+        let declared = |source: &Source| match source {
+            Source::GoogleBooks => 0,
+            Source::OpenLibrary => 1,
+            Source::Goodreads => 2,
+            Source::Amazon => 3,
+            Source::LibraryThing => 4,
+            Source::Crossref => 5,
+            #[cfg(feature = "mock")]
+            Source::Mock => 6,
+        };
+        let rank = |source: &Source| {
+            (
+                std::cmp::Reverse(priority.get(source).copied().unwrap_or(0)),
+                declared(source),
+            )
+        };
+
+        let mut values = values.iter().collect::<Vec<_>>();
+        values.sort_by_key(|(source, _)| rank(source));
+        values.into_iter().map(|(_, value)| value).collect()
+    }
+}
+
+/// Longest value, ties broken by the lowest in sorted order
+pub(crate) fn longest<'a>(values: impl Iterator<Item = &'a String>) -> Option<String> {
+    values
+        .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+        .cloned()
+}
+
+/// Value spelled the most ways when ignoring case and whitespace, ties broken by the
+/// shortest and then the lowest in sorted order. Every source contributes its own
+/// spelling, so this is the value the most sources agree on.
+pub(crate) fn most_common<'a>(values: impl Iterator<Item = &'a String>) -> Option<String> {
+    let mut groups: HashMap<String, Vec<&String>> = HashMap::new();
+
+    for value in values {
+        let key = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        groups.entry(key.to_lowercase()).or_default().push(value);
+    }
+
+    groups
+        .into_values()
+        .filter_map(|group| {
+            let count = group.len();
+            group
+                .into_iter()
+                .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+                .map(|value| (count, value))
+        })
+        .max_by(|(a_count, a), (b_count, b)| {
+            a_count
+                .cmp(b_count)
+                .then_with(|| b.len().cmp(&a.len()))
+                .then_with(|| b.cmp(a))
+        })
+        .map(|(_, value)| value.clone())
+}
+
+/// Values in sorted order
+pub(crate) fn sorted<'a>(values: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut values = values.cloned().collect::<Vec<_>>();
+    values.sort();
+    values
+}
+
+impl Metadata {
+    /// Collapses every field to a single value for display, the value picked for
+    /// each field is documented on [`MetadataOne`]. The same [`Metadata`] always
+    /// gives the same result.
+    pub fn representative(&self) -> MetadataOne {
+        MetadataOne {
+            isbn10:           self.isbn10.iter().min_by_key(|i| i.to_string()).copied(),
+            isbn13:           self.isbn13.iter().min_by_key(|i| i.to_string()).copied(),
+            title:            longest(self.title.iter()),
+            subtitle:         longest(self.subtitle.iter()),
+            series:           most_common(self.series.iter()),
+            author:           sorted(self.author.iter()),
+            description:      longest(self.description.iter()),
+            page_count:       self.page_count_representative(),
+            publisher:        most_common(self.publisher.iter()),
+            publication_date: self.publication_date.iter().min().copied(),
+            language:         self.language.iter().min().cloned(),
+            tag:              sorted(self.tag.iter()),
+            cover_image:      self.cover_image.best_url().map(str::to_owned),
+            average_rating:   self.average_rating.iter().max().cloned(),
+            ratings_count:    self.ratings_count.iter().max().copied(),
+        }
+    }
+}
+
+impl Metadata {
+    /// Page count the most sources agree on, ties broken toward the larger count.
+    ///
+    /// [`Metadata::page_count`] keeps every distinct count once, so counts within 2% of
+    /// each other are taken to agree, editions often only differ by a few pages of front
+    /// matter. Every count remains available in [`Metadata::page_count`].
+    pub fn page_count_representative(&self) -> Option<u16> {
+        let agrees = |a: u16, b: u16| (a.abs_diff(b) as u32) * 50 <= a.max(b) as u32;
+
+        self.page_count
+            .iter()
+            .map(|&count| {
+                let support = self
+                    .page_count
+                    .iter()
+                    .filter(|&&other| agrees(count, other))
+                    .count();
+                (support, count)
+            })
+            .max()
+            .map(|(_, count)| count)
+    }
+}
+
+impl Metadata {
+    /// Same as [`Metadata::representative`] but each field is taken from the source
+    /// with the highest priority that has a value for it, see [`SourcePriority`].
+    ///
+    /// Results kept apart for each source are returned by [`Metadata::from_isbn_per_source`].
+    pub fn representative_by_priority(
+        per_source: &HashMap<Source, Metadata>,
+        priority: &SourcePriority,
+    ) -> MetadataOne {
+        let ones = per_source
+            .iter()
+            .map(|(source, metadata)| (*source, metadata.representative()))
+            .collect::<Vec<_>>();
+
+        let first = |field: &str| priority.ordered(field, &ones).into_iter();
+
+        MetadataOne {
+            isbn10:           first("isbn10").find_map(|one| one.isbn10),
+            isbn13:           first("isbn13").find_map(|one| one.isbn13),
+            title:            first("title").find_map(|one| one.title.clone()),
+            subtitle:         first("subtitle").find_map(|one| one.subtitle.clone()),
+            series:           first("series").find_map(|one| one.series.clone()),
+            author:           first("author")
+                .map(|one| one.author.clone())
+                .find(|author| !author.is_empty())
+                .unwrap_or_default(),
+            description:      first("description").find_map(|one| one.description.clone()),
+            page_count:       first("page_count").find_map(|one| one.page_count),
+            publisher:        first("publisher").find_map(|one| one.publisher.clone()),
+            publication_date: first("publication_date").find_map(|one| one.publication_date),
+            language:         first("language").find_map(|one| one.language.clone()),
+            tag:              first("tag")
+                .map(|one| one.tag.clone())
+                .find(|tag| !tag.is_empty())
+                .unwrap_or_default(),
+            cover_image:      first("cover_image").find_map(|one| one.cover_image.clone()),
+            average_rating:   first("average_rating").find_map(|one| one.average_rating.clone()),
+            ratings_count:    first("ratings_count").find_map(|one| one.ratings_count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::metadata::Metadata;
+    use crate::metadata::PubDate;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn picks_representative_values() {
+        let mut metadata = Metadata::default();
+        metadata.title.insert("The Way of Kings".to_owned());
+        metadata.title.insert("Way of Kings".to_owned());
+        metadata.publisher.insert("Tor Books".to_owned());
+        metadata.publisher.insert("TOR  BOOKS".to_owned());
+        metadata.publisher.insert("Gollancz".to_owned());
+        metadata.page_count.insert(1007);
+        metadata.page_count.insert(1258);
+        metadata
+            .publication_date
+            .insert(PubDate::Full(NaiveDate::from_ymd_opt(2011, 5, 24).unwrap()));
+        metadata
+            .publication_date
+            .insert(PubDate::Full(NaiveDate::from_ymd_opt(2010, 8, 31).unwrap()));
+
+        let one = metadata.representative();
+        assert_eq!(one.title.as_deref(), Some("The Way of Kings"));
+        assert_eq!(one.publisher.as_deref(), Some("Tor Books"));
+        assert_eq!(one.page_count, Some(1258));
+        assert_eq!(
+            one.publication_date,
+            Some(PubDate::Full(NaiveDate::from_ymd_opt(2010, 8, 31).unwrap()))
+        );
+        assert_eq!(one.subtitle, None);
+        assert_eq!(one, metadata.representative());
+    }
+
+    #[test]
+    fn picks_agreed_page_count() {
+        let mut metadata = Metadata::default();
+        assert_eq!(metadata.page_count_representative(), None);
+
+        metadata.page_count.extend([320, 336, 322, 480]);
+        assert_eq!(metadata.page_count_representative(), Some(322));
+        assert_eq!(metadata.page_count().len(), 4);
+    }
+
+    #[test]
+    fn prefers_sources_by_priority() {
+        use super::SourcePriority;
+        use crate::recon::Source;
+        use std::collections::HashMap;
+
+        let mut goodreads = Metadata::default();
+        goodreads.title.insert("The Way of Kings".to_owned());
+        goodreads.description.insert("Goodreads".to_owned());
+
+        let mut google_books = Metadata::default();
+        google_books
+            .title
+            .insert("The Way of Kings: Book One".to_owned());
+        google_books.description.insert("GoogleBooks".to_owned());
+        google_books.publisher.insert("Tor Books".to_owned());
+
+        let per_source = HashMap::from([
+            (Source::Goodreads, goodreads),
+            (Source::GoogleBooks, google_books),
+        ]);
+
+        let priority = SourcePriority::new(HashMap::from([(Source::Goodreads, 1)]))
+            .field("description", HashMap::from([(Source::GoogleBooks, 1)]));
+
+        let one = Metadata::representative_by_priority(&per_source, &priority);
+        assert_eq!(one.title.as_deref(), Some("The Way of Kings"));
+        assert_eq!(one.description.as_deref(), Some("GoogleBooks"));
+        // falling back to lower priorities for missing values
+        assert_eq!(one.publisher.as_deref(), Some("Tor Books"));
+    }
+
+    #[test]
+    fn breaks_priority_ties_by_source() {
+        use super::SourcePriority;
+        use crate::recon::Source;
+        use std::collections::HashMap;
+
+        let mut library_thing = Metadata::default();
+        library_thing.title.insert("The Way of Kings".to_owned());
+
+        let mut google_books = Metadata::default();
+        google_books
+            .title
+            .insert("The Way of Kings: Book One".to_owned());
+
+        // every map iterates its sources in a different order
+        let priority = SourcePriority::default();
+        for _ in 0..8 {
+            let per_source = HashMap::from([
+                (Source::LibraryThing, library_thing.clone()),
+                (Source::GoogleBooks, google_books.clone()),
+            ]);
+            let one = Metadata::representative_by_priority(&per_source, &priority);
+            assert_eq!(one.title.as_deref(), Some("The Way of Kings: Book One"));
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::metadata::Metadata;
+use crate::recon::{ReconError, Source};
+use isbn2::Isbn;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// In-flight lookups are keyed by source and the ISBN string as it was queried
+type Key = (Source, String);
+
+type Waiters = Vec<oneshot::Sender<Result<Metadata, ReconError>>>;
+
+/// Shares a single request between concurrent lookups of the same ISBN from the same
+/// [`Source`]: the first lookup performs it, the others wait for and receive its result.
+///
+/// Only lookups that overlap are merged, an entry is removed as soon as its request
+/// completes. Errors other than [`ReconError::NotFound`], [`ReconError::RateLimited`],
+/// [`ReconError::MissingField`] and [`ReconError::Message`] reach the waiting lookups as
+/// [`ReconError::Message`], since the underlying errors cannot be cloned.
+///
+/// Clones share the same state, so a single instance can be placed in several
+/// [`ReconConfig`](crate::ReconConfig)s.
+#[derive(Clone, Debug, Default)]
+pub struct SingleFlight {
+    in_flight: Arc<Mutex<HashMap<Key, Waiters>>>,
+}
+
+/// Removes the entry of a lookup once it completes or is dropped,
+/// the waiters of a dropped lookup then perform their own request.
+struct Leader<'a> {
+    single_flight: &'a SingleFlight,
+    /// Taken by [`Leader::finish`], so the entry is removed exactly once and never
+    /// after a later lookup of the same key became the leader
+    key:           Option<Key>,
+}
+
+impl Leader<'_> {
+    fn finish(mut self) -> Waiters {
+        self.key
+            .take()
+            .and_then(|key| self.single_flight.in_flight.lock().unwrap().remove(&key))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.single_flight.in_flight.lock().unwrap().remove(&key);
+        }
+    }
+}
+
+/// Copy of `result` handed to a waiting lookup
+fn share(result: &Result<Metadata, ReconError>) -> Result<Metadata, ReconError> {
+    match result {
+        Ok(metadata) => Ok(metadata.clone()),
+        Err(ReconError::NotFound(isbn)) => Err(ReconError::NotFound(isbn.clone())),
+        Err(ReconError::RateLimited { retry_after }) => Err(ReconError::RateLimited {
+            retry_after: *retry_after,
+        }),
+        Err(ReconError::MissingField(field)) => Err(ReconError::MissingField(field.clone())),
+        Err(ReconError::Message(message)) => Err(ReconError::Message(message.clone())),
+        Err(err) => Err(ReconError::Message(err.to_string())),
+    }
+}
+
+impl SingleFlight {
+    /// Creates an instance with no lookup in flight
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct lookups currently in flight
+    pub fn len(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no lookup is in flight
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Awaits `request` for `isbn` from `source`, or the result of the identical
+    /// request already in flight.
+    pub(crate) async fn call(
+        &self,
+        source: &Source,
+        isbn: &Isbn,
+        request: impl Future<Output = Result<Metadata, ReconError>>,
+    ) -> Result<Metadata, ReconError> {
+        let key = (*source, isbn.to_string());
+
+        let waiting = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get_mut(&key) {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    in_flight.insert(key.clone(), Waiters::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(receiver) = waiting {
+            return match receiver.await {
+                Ok(result) => result,
+                // the lookup in flight was dropped before completing
+                Err(_) => request.await,
+            };
+        }
+
+        let leader = Leader {
+            single_flight: self,
+            key:           Some(key),
+        };
+        let result = request.await;
+
+        for waiter in leader.finish() {
+            let _ = waiter.send(share(&result));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SingleFlight;
+    use crate::metadata::Metadata;
+    use crate::recon::{ReconError, Source};
+    use isbn2::Isbn;
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn shares_concurrent_requests() {
+        let single_flight = SingleFlight::new();
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let requests = AtomicUsize::new(0);
+
+        let request = || async {
+            requests.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let mut metadata = Metadata::default();
+            metadata.title.insert("Dry".to_owned());
+            Ok::<_, ReconError>(metadata)
+        };
+
+        let (a, b, c) = tokio::join!(
+            single_flight.call(&Source::GoogleBooks, &isbn, request()),
+            single_flight.call(&Source::GoogleBooks, &isbn, request()),
+            single_flight.call(&Source::OpenLibrary, &isbn, request()),
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(a.unwrap(), b.unwrap());
+        assert!(c.unwrap().title.contains("Dry"));
+        assert!(single_flight.is_empty());
+
+        // shared errors keep their variant
+        let (a, b) = tokio::join!(
+            single_flight.call(&Source::GoogleBooks, &isbn, async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Err(ReconError::NotFound(isbn.clone()))
+            }),
+            single_flight.call(&Source::GoogleBooks, &isbn, request()),
+        );
+        assert!(matches!(a, Err(ReconError::NotFound(_))));
+        assert!(matches!(b, Err(ReconError::NotFound(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
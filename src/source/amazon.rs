@@ -0,0 +1,341 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use crate::metadata::{CoverImage, Metadata};
+use crate::recon::{ReconConfig, ReconError, Source};
+use crate::source::{fetch, BookSource};
+use crate::util::translater;
+use futures::future::BoxFuture;
+use isbn2::{Isbn, Isbn10, Isbn13};
+use log::debug;
+use scraper::{ElementRef, Html, Selector};
+
+#[derive(Debug)]
+/// A scraper that parses [`Metadata`] from Amazon product pages
+pub struct Amazon;
+
+/// Collects the whitespace normalized text content of an element
+fn element_text(element: &ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Trims whitespace and the left-to-right/right-to-left marks Amazon surrounds labels with
+fn trim_marks(s: &str) -> &str {
+    s.trim_matches(|c: char| c.is_whitespace() || c == '\u{200e}' || c == '\u{200f}')
+}
+
+impl Amazon {
+    /// Parses [`Metadata`] from `Amazon` product details page
+    /// This is an example of a product details page:
+    /// <https://www.amazon.com/dp/1534431004>
+    pub fn from_web_page(page: &Html) -> Result<Metadata, ReconError> {
+        let title_selector = Selector::parse("span#productTitle").unwrap();
+        let mut title = HashSet::new();
+        for element in page.select(&title_selector) {
+            title.insert(element_text(&element));
+        }
+
+        // Amazon serves a captcha or a "dogs of Amazon" page to blocked clients,
+        // neither of which has a product title.
+        if title.is_empty() {
+            return Err(ReconError::Message(
+                "Amazon product page not recognized.".to_owned(),
+            ));
+        }
+
+        let author_selector = Selector::parse("#bylineInfo span.author a").unwrap();
+        let mut author = HashSet::new();
+        for element in page.select(&author_selector) {
+            let name = element_text(&element);
+            if !name.is_empty() {
+                author.insert(name);
+            }
+        }
+
+        let description_selector =
+            Selector::parse("#bookDescription_feature_div .a-expander-content").unwrap();
+        let mut description = HashSet::new();
+        for element in page.select(&description_selector) {
+            description.insert(element_text(&element));
+        }
+
+        let cover_image_selector = Selector::parse("img#landingImage, img#imgBlkFront").unwrap();
+        let mut medium = HashSet::new();
+        let mut large = HashSet::new();
+        for element in page.select(&cover_image_selector) {
+            if let Some(src) = element.value().attr("src") {
+                medium.insert(src.to_owned());
+            }
+            if let Some(src) = element.value().attr("data-old-hires") {
+                if !src.is_empty() {
+                    large.insert(src.to_owned());
+                }
+            }
+        }
+        let cover_image = CoverImage {
+            medium,
+            large,
+            ..CoverImage::default()
+        };
+
+        // Product details are rendered as a list of "Label : Value" bullets, e.g.
+        // "Publisher : Saga Press; Reprint edition (March 3, 2020)"
+        let detail_selector = Selector::parse("#detailBullets_feature_div li").unwrap();
+        let mut isbn10 = HashSet::new();
+        let mut isbn13 = HashSet::new();
+        let mut publisher = HashSet::new();
+        let mut publication_date = HashSet::new();
+        let mut page_count = HashSet::new();
+        let mut language = HashSet::new();
+        for element in page.select(&detail_selector) {
+            let text = element_text(&element);
+            let (label, value) = match text.split_once(':') {
+                Some((label, value)) => (trim_marks(label), trim_marks(value)),
+                None => continue,
+            };
+
+            match label {
+                "ISBN-10" => isbn10.extend(Isbn10::from_str(value).ok()),
+                "ISBN-13" => isbn13.extend(Isbn13::from_str(value).ok()),
+                "Publisher" => {
+                    let (name, date) = match value.split_once('(') {
+                        Some((name, date)) => (name, Some(date.trim_end_matches(')'))),
+                        None => (value, None),
+                    };
+                    let name = name.split(';').next().unwrap_or_default().trim();
+                    if !name.is_empty() {
+                        publisher.insert(name.to_owned());
+                    }
+                    publication_date.extend(translater::publication_date(date));
+                }
+                "Publication date" => {
+                    publication_date.extend(translater::publication_date(Some(value)))
+                }
+                "Language" => {
+                    language.insert(translater::normalize_language(value));
+                }
+                "Paperback" | "Hardcover" | "Print length" => {
+                    page_count.extend(
+                        value
+                            .chars()
+                            .filter(|c| c.is_ascii_digit())
+                            .collect::<String>()
+                            .parse::<u16>()
+                            .ok(),
+                    );
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(Metadata {
+            isbn10,
+            isbn13,
+            oclc: translater::empty(),
+            identifiers: HashMap::new(),
+            title,
+            author: translater::authors(author),
+            description,
+            page_count,
+            publisher,
+            publication_date,
+            language,
+            subtitle: HashSet::new(),
+            series: HashSet::new(),
+            tag: HashSet::new(),
+            cover_image,
+            average_rating: HashSet::new(),
+            ratings_count: HashSet::new(),
+            table_of_contents: Vec::new(),
+        })
+    }
+
+    /// Performs an ISBN search using Amazon product pages
+    /// For books, Amazon's product identifier is the ISBN-10,
+    /// so ISBN-13s outside of the `978` prefix cannot be looked up
+    /// and fail with [`ReconError::NotFound`] without a request.
+    pub async fn from_isbn(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &isbn2::Isbn,
+    ) -> Result<Metadata, ReconError> {
+        let isbn10 = match isbn {
+            Isbn::_10(isbn10) => *isbn10,
+            Isbn::_13(isbn13) => match Isbn10::try_from(*isbn13) {
+                Ok(isbn10) => isbn10,
+                // a valid `979` ISBN has no ISBN-10 form
+                Err(_) => return Err(ReconError::NotFound(isbn.clone())),
+            },
+        };
+
+        let req = format!(
+            "{}/dp/{}",
+            config.api_base_url(&Source::Amazon),
+            urlencoding::encode(&isbn10.to_string())
+        );
+
+        debug!("ISBN: {:#?}", &isbn);
+        debug!("Request: {:#?}", &req);
+
+        let response = fetch(client, config, &req)
+            .await?
+            .text()
+            .await
+            .map_err(ReconError::connection)?;
+
+        debug!("Response: {:#?}", &response);
+
+        // `Html` is not `Send`, parse without holding it across an `.await`
+        let page = Html::parse_document(&response);
+
+        translater::with_config(config, || Self::from_web_page(&page))
+    }
+
+    /// Performs a descriptive search using Amazon book search
+    pub async fn from_description(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        description: &str,
+    ) -> Result<Vec<Isbn>, ReconError> {
+        let req = format!(
+            "{}/s?k={}&i=stripbooks",
+            config.api_base_url(&Source::Amazon),
+            urlencoding::encode(description)
+        );
+
+        debug!("Description: {:#?}", &description);
+        debug!("Request: {:#?}", &req);
+
+        let response = fetch(client, config, &req)
+            .await?
+            .text()
+            .await
+            .map_err(ReconError::connection)?;
+
+        debug!("Response: {:#?}", &response);
+
+        // Print books are listed under their ISBN-10, other formats (e.g. Kindle editions)
+        // use an ASIN which fails to parse as an ISBN and is discarded.
+        let page = Html::parse_document(&response);
+        let result_selector = Selector::parse("div[data-asin]").unwrap();
+        let mut isbn_list = page
+            .select(&result_selector)
+            .filter_map(|element| element.value().attr("data-asin"))
+            .filter_map(|asin| Isbn::from_str(asin).ok())
+            .collect::<Vec<_>>();
+
+        isbn_list.truncate(config.description_limit);
+
+        Ok(isbn_list)
+    }
+}
+
+impl BookSource for Amazon {
+    fn from_isbn<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>> {
+        Box::pin(Self::from_isbn(client, config, isbn))
+    }
+
+    fn from_description<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(Self::from_description(client, config, description))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn init_logger() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    #[tokio::test]
+    async fn reports_979_isbn_not_found() {
+        use super::Amazon;
+        use crate::recon::{ReconConfig, ReconError, Source};
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        init_logger();
+
+        // `.invalid` never resolves, the lookup must fail before any request
+        let config = ReconConfig::new().base_url(Source::Amazon, "http://books.invalid");
+        let isbn = Isbn::from_str("9791032305690").unwrap();
+
+        assert!(matches!(
+            Amazon::from_isbn(&config.client().unwrap(), &config, &isbn).await,
+            Err(ReconError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn parses_from_web_page() {
+        use super::Amazon;
+        use log::debug;
+        use scraper::Html;
+
+        init_logger();
+
+        let page = Html::parse_document(
+            r##"
+            <span id="productTitle"> This Is How You Lose the Time War </span>
+            <div id="bylineInfo">
+              <span class="author"><a href="#">Amal El-Mohtar</a></span>
+              <span class="author"><a href="#">Max Gladstone</a></span>
+            </div>
+            <img id="landingImage" src="https://m.media-amazon.com/a._SY344_.jpg"
+                 data-old-hires="https://m.media-amazon.com/a._SL1500_.jpg">
+            <div id="detailBullets_feature_div"><ul>
+              <li><span><span class="a-text-bold">Publisher &rlm; : &lrm;</span>
+                  <span>Saga Press; Reprint edition (March 3, 2020)</span></span></li>
+              <li><span><span class="a-text-bold">Language &rlm; : &lrm;</span>
+                  <span>English</span></span></li>
+              <li><span><span class="a-text-bold">Paperback &rlm; : &lrm;</span>
+                  <span>208 pages</span></span></li>
+              <li><span><span class="a-text-bold">ISBN-10 &rlm; : &lrm;</span>
+                  <span>1534431004</span></span></li>
+              <li><span><span class="a-text-bold">ISBN-13 &rlm; : &lrm;</span>
+                  <span>978-1534431003</span></span></li>
+            </ul></div>
+            "##,
+        );
+
+        let resp = Amazon::from_web_page(&page);
+        debug!("Response: {:#?}", resp);
+
+        let metadata = resp.unwrap();
+        assert!(metadata.title.contains("This Is How You Lose the Time War"));
+        assert!(metadata.author.contains("Max Gladstone"));
+        assert!(metadata.publisher.contains("Saga Press"));
+        assert!(metadata.page_count.contains(&208));
+        assert!(metadata.language.contains("en"));
+        assert_eq!(metadata.isbn10.len(), 1);
+        assert_eq!(metadata.isbn13.len(), 1);
+        assert_eq!(metadata.publication_date.len(), 1);
+        assert_eq!(metadata.cover_image.large.len(), 1);
+    }
+
+    #[test]
+    fn rejects_unrecognized_web_page() {
+        use super::Amazon;
+        use scraper::Html;
+
+        init_logger();
+
+        let page = Html::parse_document("<p>Enter the characters you see below</p>");
+        assert!(Amazon::from_web_page(&page).is_err())
+    }
+}
@@ -0,0 +1,237 @@
+use crate::metadata::{Metadata, PubDate};
+use crate::recon::{ReconConfig, ReconError, Source};
+use crate::source::{fetch, isbn_forms, BookSource};
+use crate::util::translater;
+use chrono::NaiveDate;
+use futures::future::BoxFuture;
+use isbn2::Isbn;
+use log::debug;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    #[serde(default)]
+    items: Vec<Work>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct Work {
+    title:     Vec<String>,
+    subtitle:  Vec<String>,
+    author:    Vec<Person>,
+    publisher: Option<String>,
+    published: Option<DateParts>,
+    /// Fallback for works without a `published` date
+    issued:    Option<DateParts>,
+    #[serde(rename = "ISBN")]
+    isbn:      Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Person {
+    given:  Option<String>,
+    family: Option<String>,
+    /// Set instead of `given` and `family` for organizations
+    name:   Option<String>,
+}
+
+/// `{ "date-parts": [[2018, 10, 2]] }`, with as many parts as the date is precise,
+/// `[[null]]` when unknown
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DateParts {
+    #[serde(rename = "date-parts")]
+    date_parts: Vec<Vec<Option<i32>>>,
+}
+
+impl DateParts {
+    fn to_pub_dates(&self) -> impl Iterator<Item = PubDate> + '_ {
+        self.date_parts.iter().filter_map(|parts| {
+            let mut parts = parts.iter().map_while(|part| *part);
+
+            let year = parts.next()?;
+            match (parts.next(), parts.next()) {
+                (None, _) => Some(PubDate::Year(year)),
+                (Some(month), None) => (1..=12)
+                    .contains(&month)
+                    .then_some(PubDate::YearMonth(year, month as u32)),
+                (Some(month), Some(day)) => {
+                    NaiveDate::from_ymd_opt(year, month as u32, day as u32).map(PubDate::Full)
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+/// Parses [`Metadata`] from Crossref works registered with an ISBN
+pub struct Crossref;
+
+impl Crossref {
+    /// Performs an ISBN search using Crossref's REST API.
+    /// <https://api.crossref.org/swagger-ui/index.html>
+    pub async fn from_isbn(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &Isbn,
+    ) -> Result<Metadata, ReconError> {
+        let req = format!(
+            "{}/works?filter=isbn:{}",
+            config.api_base_url(&Source::Crossref),
+            urlencoding::encode(&isbn_forms(isbn)[0])
+        );
+
+        debug!("ISBN: {:#?}", &isbn);
+
+        let response = fetch(client, config, &req)
+            .await?
+            .text()
+            .await
+            .map_err(ReconError::connection)?;
+
+        debug!("Response: {:#?}", &response);
+
+        translater::with_config(config, || Self::from_response(isbn, &response))
+    }
+
+    /// Merges every work of a `/works` response into one [`Metadata`]
+    fn from_response(isbn: &Isbn, response: &str) -> Result<Metadata, ReconError> {
+        let response: Response = serde_json::from_str(response).map_err(ReconError::JSONParse)?;
+        let mut metadata = Metadata::default();
+
+        for work in response.message.items {
+            metadata = metadata + Self::from_work(work);
+        }
+
+        match metadata.is_empty() {
+            true => Err(ReconError::NotFound(isbn.clone())),
+            false => Ok(metadata),
+        }
+    }
+
+    fn from_work(work: Work) -> Metadata {
+        let mut metadata = Metadata {
+            subtitle: work.subtitle.into_iter().collect(),
+            author: translater::authors(work.author.iter().filter_map(|person| {
+                match (&person.given, &person.family) {
+                    (Some(given), Some(family)) => Some(format!("{} {}", given, family)),
+                    (None, Some(family)) => Some(family.clone()),
+                    _ => person.name.clone(),
+                }
+            })),
+            publisher: translater::string(work.publisher),
+            publication_date: work
+                .published
+                .as_ref()
+                .or(work.issued.as_ref())
+                .map(|date| date.to_pub_dates().collect())
+                .unwrap_or_default(),
+            ..Metadata::default()
+        };
+
+        for title in &work.title {
+            metadata
+                .title
+                .extend(translater::title(&Some(title.clone())));
+            metadata
+                .series
+                .extend(translater::series(&Some(title.clone())));
+        }
+
+        for isbn in &work.isbn {
+            match Isbn::from_str(&isbn.replace('-', "")) {
+                Ok(Isbn::_10(isbn10)) => {
+                    metadata.isbn10.insert(isbn10);
+                }
+                Ok(Isbn::_13(isbn13)) => {
+                    metadata.isbn13.insert(isbn13);
+                }
+                Err(_) => debug!("Skipping invalid ISBN {}", isbn),
+            }
+        }
+
+        metadata.normalize_isbns();
+
+        metadata
+    }
+}
+
+impl BookSource for Crossref {
+    fn from_isbn<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>> {
+        Box::pin(Self::from_isbn(client, config, isbn))
+    }
+
+    fn from_description<'a>(
+        &'a self,
+        _client: &'a reqwest::Client,
+        _config: &'a ReconConfig,
+        _description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(async move {
+            Err(ReconError::Message(
+                "Crossref has no descriptive search.".to_owned(),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Crossref;
+    use crate::metadata::PubDate;
+    use crate::recon::ReconError;
+    use isbn2::Isbn;
+    use std::str::FromStr;
+
+    #[test]
+    fn parses_from_response() {
+        let response = r#"{
+  "status": "ok",
+  "message-type": "work-list",
+  "message": {
+    "total-results": 1,
+    "items": [{
+      "title": ["Dry"],
+      "author": [
+        { "given": "Neal", "family": "Shusterman", "sequence": "first" },
+        { "given": "Jarrod", "family": "Shusterman", "sequence": "additional" }
+      ],
+      "publisher": "Simon and Schuster",
+      "published": { "date-parts": [[2018, 10]] },
+      "ISBN": ["9781534431003", "978-1-5344-3102-7"],
+      "type": "monograph"
+    }]
+  }
+}"#;
+
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let metadata = Crossref::from_response(&isbn, response).unwrap();
+        assert!(metadata.title.contains("Dry"));
+        assert!(metadata.author.contains("Neal Shusterman"));
+        assert_eq!(metadata.author.len(), 2);
+        assert!(metadata.publisher.contains("Simon and Schuster"));
+        assert!(metadata
+            .publication_date
+            .contains(&PubDate::YearMonth(2018, 10)));
+        assert_eq!(metadata.isbn13.len(), 2);
+
+        let empty = r#"{ "status": "ok", "message": { "items": [] } }"#;
+        assert!(matches!(
+            Crossref::from_response(&isbn, empty),
+            Err(ReconError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,470 @@
+use crate::metadata::Metadata;
+use crate::recon::ReconError;
+use crate::util::{inflate::inflate, translater};
+use isbn2::Isbn;
+use log::debug;
+use std::convert::TryInto;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Dublin Core elements namespace used by the OPF `<metadata>` element
+const DC: &str = "http://purl.org/dc/elements/1.1/";
+
+/// Media type of the EPUB 2 navigation control file
+const NCX_MEDIA_TYPE: &str = "application/x-dtbncx+xml";
+
+/// Files of an EPUB, either unpacked into a directory or still in its zip archive
+enum Container {
+    Directory(PathBuf),
+    Archive(Vec<u8>),
+}
+
+impl Container {
+    fn open(path: &Path) -> Result<Self, ReconError> {
+        match path.is_dir() {
+            true => Ok(Container::Directory(path.to_owned())),
+            false => Ok(Container::Archive(fs::read(path).map_err(ReconError::Io)?)),
+        }
+    }
+
+    /// Reads the file at `name`, relative to the root of the EPUB
+    fn read(&self, name: &str) -> Result<String, ReconError> {
+        let bytes = match self {
+            Container::Directory(root) => fs::read(root.join(name)).map_err(ReconError::Io)?,
+            Container::Archive(archive) => entry(archive, name)?,
+        };
+
+        String::from_utf8(bytes).map_err(|_| ReconError::Message(format!("{} is not UTF-8", name)))
+    }
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Option<usize> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as usize)
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<usize> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?) as usize)
+}
+
+/// Finds `name` in the central directory of a zip `archive` and returns its contents,
+/// decompressing deflated entries.
+fn entry(archive: &[u8], name: &str) -> Result<Vec<u8>, ReconError> {
+    let malformed = || ReconError::Message("Malformed EPUB archive".to_owned());
+
+    // end of central directory record, followed by a comment of up to 64KiB
+    let end = (0..archive.len().saturating_sub(21))
+        .rev()
+        .find(|&at| archive[at..].starts_with(b"PK\x05\x06"))
+        .ok_or_else(malformed)?;
+    let entries = u16_at(archive, end + 10).ok_or_else(malformed)?;
+    let mut at = u32_at(archive, end + 16).ok_or_else(malformed)?;
+
+    for _ in 0..entries {
+        if !archive
+            .get(at..)
+            .ok_or_else(malformed)?
+            .starts_with(b"PK\x01\x02")
+        {
+            return Err(malformed());
+        }
+
+        let method = u16_at(archive, at + 10).ok_or_else(malformed)?;
+        let compressed_size = u32_at(archive, at + 20).ok_or_else(malformed)?;
+        let size = u32_at(archive, at + 24).ok_or_else(malformed)?;
+        let name_len = u16_at(archive, at + 28).ok_or_else(malformed)?;
+        let extra_len = u16_at(archive, at + 30).ok_or_else(malformed)?;
+        let comment_len = u16_at(archive, at + 32).ok_or_else(malformed)?;
+        let local = u32_at(archive, at + 42).ok_or_else(malformed)?;
+        let entry_name = archive
+            .get(at + 46..at + 46 + name_len)
+            .ok_or_else(malformed)?;
+
+        if entry_name == name.as_bytes() {
+            let data = local
+                + 30
+                + u16_at(archive, local + 26).ok_or_else(malformed)?
+                + u16_at(archive, local + 28).ok_or_else(malformed)?;
+            let data = archive
+                .get(data..data + compressed_size)
+                .ok_or_else(malformed)?;
+
+            return match method {
+                0 => Ok(data.to_vec()),
+                8 => inflate(data, size).ok_or_else(|| {
+                    ReconError::Message(format!("{} is not a valid deflate stream", name))
+                }),
+                method => Err(ReconError::Message(format!(
+                    "{} uses unsupported compression method {}",
+                    name, method
+                ))),
+            };
+        }
+
+        at += 46 + name_len + extra_len + comment_len;
+    }
+
+    Err(ReconError::Message(format!(
+        "{} is missing from the EPUB",
+        name
+    )))
+}
+
+/// Reads the [`Metadata`] of the EPUB at `path`
+pub(crate) fn from_path(path: &Path) -> Result<Metadata, ReconError> {
+    let container = Container::open(path)?;
+
+    let container_xml = container.read("META-INF/container.xml")?;
+    let opf_path = opf_path(&container_xml)?;
+    debug!("Reading EPUB package document {}", opf_path);
+
+    let opf = container.read(&opf_path)?;
+    let mut metadata = from_opf(&opf, &opf_path)?;
+
+    // a malformed navigation document should not fail the rest of the metadata
+    match table_of_contents(&container, &opf, &opf_path) {
+        Ok(entries) => metadata.table_of_contents = entries,
+        Err(err) => debug!("Skipping EPUB table of contents: {}", err),
+    }
+
+    Ok(metadata)
+}
+
+/// Resolves a manifest `href`, relative to the package document, to a path within the EPUB
+fn resolve(opf_path: &str, href: &str) -> String {
+    match opf_path.rfind('/') {
+        Some(slash) => format!("{}/{}", &opf_path[..slash], href),
+        None => href.to_owned(),
+    }
+}
+
+/// Path of the OPF package document listed in `META-INF/container.xml`
+fn opf_path(container_xml: &str) -> Result<String, ReconError> {
+    let document = roxmltree::Document::parse(container_xml)
+        .map_err(|err| ReconError::Message(err.to_string()))?;
+
+    document
+        .descendants()
+        .find(|node| node.has_tag_name("rootfile"))
+        .and_then(|node| node.attribute("full-path"))
+        .map(str::to_owned)
+        .ok_or_else(|| ReconError::MissingField("rootfile".to_owned()))
+}
+
+/// Translates the Dublin Core fields of an OPF package document into [`Metadata`],
+/// the cover image is referenced by its path within the EPUB.
+fn from_opf(opf: &str, opf_path: &str) -> Result<Metadata, ReconError> {
+    let document =
+        roxmltree::Document::parse(opf).map_err(|err| ReconError::Message(err.to_string()))?;
+    let mut metadata = Metadata::default();
+
+    for node in document
+        .descendants()
+        .filter(|node| node.tag_name().namespace() == Some(DC))
+    {
+        let text = match node.text().map(str::trim) {
+            Some(text) if !text.is_empty() => text,
+            _ => continue,
+        };
+
+        match node.tag_name().name() {
+            "title" => metadata
+                .title
+                .extend(translater::title(&Some(text.to_owned()))),
+            "creator" => metadata.author.extend(translater::authors(vec![text])),
+            "identifier" => {
+                let digits = text.trim_start_matches("urn:isbn:").replace('-', "");
+                match Isbn::from_str(&digits) {
+                    Ok(Isbn::_10(isbn10)) => {
+                        metadata.isbn10.insert(isbn10);
+                    }
+                    Ok(Isbn::_13(isbn13)) => {
+                        metadata.isbn13.insert(isbn13);
+                    }
+                    Err(_) => {} // UUIDs and other non-ISBN identifiers
+                }
+            }
+            "publisher" => {
+                metadata.publisher.insert(text.to_owned());
+            }
+            "date" => metadata
+                .publication_date
+                .extend(translater::publication_date(Some(text))),
+            "language" => metadata
+                .language
+                .extend(translater::language(Some(text.to_owned()))),
+            "subject" => metadata.tag.extend(translater::tags(text)),
+            "description" => metadata
+                .description
+                .extend(translater::html(Some(text.to_owned()))),
+            _ => {}
+        }
+    }
+
+    if let Some(href) = cover_href(&document) {
+        metadata.cover_image.large.insert(resolve(opf_path, href));
+    }
+
+    metadata.normalize_isbns();
+
+    Ok(metadata)
+}
+
+/// Manifest `href` of the cover image, marked with the EPUB 3 `cover-image` property
+/// or referenced by the EPUB 2 `<meta name="cover">` element
+fn cover_href<'a>(document: &'a roxmltree::Document) -> Option<&'a str> {
+    let items = || {
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name("item"))
+    };
+
+    items()
+        .find(|item| {
+            item.attribute("properties")
+                .is_some_and(|properties| properties.split_whitespace().any(|p| p == "cover-image"))
+        })
+        .or_else(|| {
+            let id = document
+                .descendants()
+                .find(|node| node.has_tag_name("meta") && node.attribute("name") == Some("cover"))?
+                .attribute("content")?;
+            items().find(|item| item.attribute("id") == Some(id))
+        })
+        .and_then(|item| item.attribute("href"))
+}
+
+/// Entries of the EPUB 3 navigation document's `toc` nav, or of the EPUB 2 NCX,
+/// in reading order
+fn table_of_contents(
+    container: &Container,
+    opf: &str,
+    opf_path: &str,
+) -> Result<Vec<String>, ReconError> {
+    let document =
+        roxmltree::Document::parse(opf).map_err(|err| ReconError::Message(err.to_string()))?;
+    let items = || {
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name("item"))
+    };
+
+    let nav = items().find(|item| {
+        item.attribute("properties")
+            .is_some_and(|properties| properties.split_whitespace().any(|p| p == "nav"))
+    });
+    let ncx = || items().find(|item| item.attribute("media-type") == Some(NCX_MEDIA_TYPE));
+
+    let (href, is_nav) = match (nav, ncx()) {
+        (Some(nav), _) => (nav.attribute("href"), true),
+        (None, Some(ncx)) => (ncx.attribute("href"), false),
+        (None, None) => return Ok(Vec::new()),
+    };
+    let href = href.ok_or_else(|| ReconError::MissingField("href".to_owned()))?;
+
+    let navigation = container.read(&resolve(opf_path, href))?;
+    Ok(match is_nav {
+        true => nav_entries(&navigation)?,
+        false => ncx_entries(&navigation)?,
+    })
+}
+
+/// Text of `node` with its whitespace collapsed
+fn collapsed_text(node: roxmltree::Node) -> String {
+    node.descendants()
+        .filter(|node| node.is_text())
+        .filter_map(|node| node.text())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Links of `<nav epub:type="toc">`, or of the first `<nav>` if none is marked
+fn nav_entries(xhtml: &str) -> Result<Vec<String>, ReconError> {
+    let document =
+        roxmltree::Document::parse(xhtml).map_err(|err| ReconError::Message(err.to_string()))?;
+    let navs = || {
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name("nav"))
+    };
+
+    let toc = navs()
+        .find(|nav| {
+            nav.attributes()
+                .iter()
+                .any(|attribute| attribute.name() == "type" && attribute.value() == "toc")
+        })
+        .or_else(|| navs().next());
+
+    Ok(toc
+        .into_iter()
+        .flat_map(|toc| toc.descendants().filter(|node| node.has_tag_name("a")))
+        .map(collapsed_text)
+        .filter(|entry| !entry.is_empty())
+        .collect())
+}
+
+/// Labels of the NCX `navPoint`s, nested ones after their parent
+fn ncx_entries(ncx: &str) -> Result<Vec<String>, ReconError> {
+    let document =
+        roxmltree::Document::parse(ncx).map_err(|err| ReconError::Message(err.to_string()))?;
+
+    Ok(document
+        .descendants()
+        .filter(|node| node.has_tag_name("navLabel"))
+        .map(collapsed_text)
+        .filter(|entry| !entry.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{entry, from_opf, nav_entries, ncx_entries, opf_path};
+    use crate::metadata::Metadata;
+
+    const OPF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>Dry</dc:title>
+    <dc:creator>Neal Shusterman</dc:creator>
+    <dc:creator>Jarrod Shusterman</dc:creator>
+    <dc:identifier id="uuid">urn:uuid:0a7e8f3c-1b2d-4e5f-8a9b-0c1d2e3f4a5b</dc:identifier>
+    <dc:identifier id="isbn">urn:isbn:978-1-5344-3100-3</dc:identifier>
+    <dc:publisher>Simon and Schuster</dc:publisher>
+    <dc:date>2018-10-02</dc:date>
+    <dc:language>en</dc:language>
+    <dc:subject>Young Adult Fiction, Dystopian</dc:subject>
+    <meta name="cover" content="cover-img"/>
+  </metadata>
+  <manifest>
+    <item id="cover-img" href="images/cover.jpg" media-type="image/jpeg"/>
+  </manifest>
+</package>"#;
+
+    #[test]
+    fn parses_opf() {
+        let metadata = from_opf(OPF, "OEBPS/content.opf").unwrap();
+
+        assert!(metadata.title().contains("Dry"));
+        assert_eq!(metadata.author().len(), 2);
+        assert_eq!(metadata.isbn13().len(), 1);
+        assert_eq!(metadata.isbn10().len(), 1);
+        assert!(metadata.publisher().contains("Simon and Schuster"));
+        assert!(metadata
+            .publication_date()
+            .iter()
+            .any(|date| date.year() == 2018));
+        assert!(metadata.language().contains("en"));
+        assert!(metadata.tag().contains("young-adult-fiction"));
+        assert!(metadata
+            .cover_image()
+            .large()
+            .contains("OEBPS/images/cover.jpg"));
+    }
+
+    #[test]
+    fn reads_table_of_contents() {
+        let nav = r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+  <body>
+    <nav epub:type="landmarks"><ol><li><a href="cover.xhtml">Cover</a></li></ol></nav>
+    <nav epub:type="toc">
+      <ol>
+        <li><a href="ch1.xhtml">Part One:
+          <span>Day One</span></a>
+          <ol><li><a href="ch1.xhtml#s1">Saturday, June 4</a></li></ol>
+        </li>
+        <li><a href="ch2.xhtml">Part Two</a></li>
+      </ol>
+    </nav>
+  </body>
+</html>"#;
+        assert_eq!(
+            nav_entries(nav).unwrap(),
+            vec!["Part One: Day One", "Saturday, June 4", "Part Two"]
+        );
+
+        let ncx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <navMap>
+    <navPoint id="p1" playOrder="1">
+      <navLabel><text>Part One</text></navLabel>
+      <content src="ch1.xhtml"/>
+      <navPoint id="p2" playOrder="2">
+        <navLabel><text>Saturday, June 4</text></navLabel>
+        <content src="ch1.xhtml#s1"/>
+      </navPoint>
+    </navPoint>
+  </navMap>
+</ncx>"#;
+        assert_eq!(
+            ncx_entries(ncx).unwrap(),
+            vec!["Part One", "Saturday, June 4"]
+        );
+    }
+
+    #[test]
+    fn reads_stored_zip_entries() {
+        let container = br#"<?xml version="1.0"?>
+<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0">
+  <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#;
+        let name = b"META-INF/container.xml";
+
+        // a single stored entry: local header, data, central directory, end record
+        let mut archive = Vec::new();
+        archive.extend(b"PK\x03\x04");
+        archive.extend([0; 4]);
+        archive.extend(0u16.to_le_bytes()); // stored
+        archive.extend([0; 8]);
+        archive.extend((container.len() as u32).to_le_bytes());
+        archive.extend((container.len() as u32).to_le_bytes());
+        archive.extend((name.len() as u16).to_le_bytes());
+        archive.extend(0u16.to_le_bytes());
+        archive.extend(&name[..]);
+        archive.extend(&container[..]);
+
+        let central = archive.len() as u32;
+        archive.extend(b"PK\x01\x02");
+        archive.extend([0; 6]);
+        archive.extend(0u16.to_le_bytes()); // stored
+        archive.extend([0; 8]);
+        archive.extend((container.len() as u32).to_le_bytes());
+        archive.extend((container.len() as u32).to_le_bytes());
+        archive.extend((name.len() as u16).to_le_bytes());
+        archive.extend([0; 12]);
+        archive.extend(0u32.to_le_bytes()); // local header offset
+        archive.extend(&name[..]);
+
+        let central_len = archive.len() as u32 - central;
+        archive.extend(b"PK\x05\x06");
+        archive.extend([0; 6]);
+        archive.extend(1u16.to_le_bytes());
+        archive.extend(central_len.to_le_bytes());
+        archive.extend(central.to_le_bytes());
+        archive.extend(0u16.to_le_bytes());
+
+        let container_xml = entry(&archive, "META-INF/container.xml").unwrap();
+        let path = opf_path(std::str::from_utf8(&container_xml).unwrap()).unwrap();
+        assert_eq!(path, "OEBPS/content.opf");
+        assert!(entry(&archive, "mimetype").is_err());
+    }
+
+    #[test]
+    fn reads_deflated_epub() {
+        // packed as usual, with only `mimetype` stored
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dry.epub");
+        let metadata = Metadata::from_epub(&path).unwrap();
+
+        assert!(metadata.title().contains("Dry"));
+        assert_eq!(metadata.author().len(), 2);
+        assert_eq!(metadata.isbn13().len(), 1);
+        assert!(metadata.publisher().contains("Simon and Schuster"));
+        assert_eq!(
+            metadata.table_of_contents(),
+            ["Part One: Day One", "Saturday, June 4", "Part Two"]
+        );
+    }
+}
@@ -1,23 +1,217 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use crate::metadata::{CoverImage, Metadata};
-use crate::recon::ReconError;
+use crate::recon::{ReconConfig, ReconError, Source};
+use crate::source::{fetch, BookSource};
+use crate::util::translater;
+use futures::future::{join_all, BoxFuture};
 use isbn2::{Isbn, Isbn10, Isbn13};
 use log::debug;
+use once_cell::sync::Lazy;
 use scraper::{Html, Selector};
+use serde::Deserialize;
+
+// Selectors are compiled once, on first use
+static TITLE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("h1#bookTitle").unwrap());
+static SERIES_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("h2#bookSeries a").unwrap());
+static AUTHOR_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"a.authorName span[itemprop="name"]"#).unwrap());
+static TAG_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("a.actionLinkLite.bookPageGenreLink").unwrap());
+static LANGUAGE_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"div[itemprop="inLanguage"]"#).unwrap());
+static ISBN_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"span[itemprop="isbn"]"#).unwrap());
+static DESCRIPTION_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"div#description span[style="display:none"]"#).unwrap());
+static COVER_IMAGE_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("img#coverImage").unwrap());
+static AVERAGE_RATING_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"span[itemprop="ratingValue"]"#).unwrap());
+static RATINGS_COUNT_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"[itemprop="ratingCount"]"#).unwrap());
+static PAGE_COUNT_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"span[itemprop="numberOfPages"]"#).unwrap());
+static JSON_LD_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"script[type="application/ld+json"]"#).unwrap());
+static BOOK_TITLE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("a.bookTitle").unwrap());
+static SEARCH_HEADER_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("h3.searchSubNavContainer").unwrap());
 
 #[derive(Debug)]
-/// A wrapper around [`Metadata`] for deserialization
-pub struct Goodreads(Metadata);
+/// A scraper that parses [`Metadata`] from Goodreads web pages
+pub struct Goodreads;
+
+/// Strips the size tokens Goodreads inserts before the extension of resized covers,
+/// such as `_SX98_` or `_SX318_SY475_`, returning the URL of the original image.
+///
+/// ".../43352954._SX98_.jpg" -> Some(".../43352954.jpg")
+/// ".../43352954.jpg" -> None
+fn full_size_cover(url: &str) -> Option<String> {
+    let extension = url.rfind('.')?;
+    let (stem, extension) = url.split_at(extension);
+    let tokens = stem.rfind("._")?;
+
+    let resized = stem[tokens + 2..]
+        .strip_suffix('_')?
+        .split('_')
+        .all(|token| {
+            let size = token
+                .strip_prefix("SX")
+                .or_else(|| token.strip_prefix("SY"));
+            size.is_some_and(|size| !size.is_empty() && size.chars().all(|c| c.is_ascii_digit()))
+        });
+
+    match resized {
+        true => Some(format!("{}{}", &stem[..tokens], extension)),
+        false => None,
+    }
+}
+
+/// Adds a cover image URL, as a thumbnail of the full size image if it is resized
+fn insert_cover(cover_image: &mut CoverImage, src: &str) {
+    match full_size_cover(src) {
+        Some(full_size) => {
+            cover_image.thumbnail.insert(src.to_owned());
+            cover_image.extra_large.insert(full_size);
+        }
+        None => {
+            cover_image.large.insert(src.to_owned());
+        }
+    }
+}
+
+/// schema.org `Book` embedded as JSON-LD in book details pages
+#[derive(Debug, Deserialize)]
+struct JsonLdBook {
+    #[serde(rename = "@type")]
+    kind:             String,
+    name:             Option<String>,
+    image:            Option<String>,
+    #[serde(rename = "numberOfPages")]
+    number_of_pages:  Option<u16>,
+    #[serde(rename = "inLanguage")]
+    in_language:      Option<String>,
+    isbn:             Option<String>,
+    #[serde(default)]
+    author:           JsonLdAuthors,
+    #[serde(rename = "aggregateRating")]
+    aggregate_rating: Option<JsonLdRating>,
+}
+
+/// A single `Person` or a list of them
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonLdAuthors {
+    One(JsonLdPerson),
+    Many(Vec<JsonLdPerson>),
+}
+
+impl Default for JsonLdAuthors {
+    fn default() -> Self {
+        JsonLdAuthors::Many(Vec::new())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonLdPerson {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonLdRating {
+    #[serde(rename = "ratingValue")]
+    rating_value: Option<serde_json::Number>,
+    #[serde(rename = "ratingCount")]
+    rating_count: Option<u32>,
+}
 
 impl Goodreads {
     /// Parses [`Metadata`] from `Goodreads` book details page
     /// This is an example of a book details page:
     /// <https://www.goodreads.com/book/show/53870787-this-is-how-you-lose-the-time-war>
-    pub async fn from_web_page(page: &Html) -> Metadata {
-        let title_selector = Selector::parse("h1#bookTitle").unwrap();
+    ///
+    /// The JSON-LD `Book` embedded in the page is preferred, pages of the older layout
+    /// without one are scraped with CSS selectors instead.
+    /// Fails with [`ReconError::Message`] if neither is found,
+    /// which is the case when Goodreads changed its markup.
+    pub fn from_web_page(page: &Html) -> Result<Metadata, ReconError> {
+        let metadata = match Self::from_json_ld(page) {
+            Some(metadata) => metadata,
+            None => Self::from_selectors(page),
+        };
+
+        // every field comes from the page, an empty result means nothing was recognized
+        match metadata.is_empty() {
+            true => Err(ReconError::Message(
+                "Goodreads page layout not recognized".to_owned(),
+            )),
+            false => Ok(metadata),
+        }
+    }
+
+    /// Parses the first `<script type="application/ld+json">` describing a `Book`
+    fn from_json_ld(page: &Html) -> Option<Metadata> {
+        let book = page
+            .select(&JSON_LD_SELECTOR)
+            .filter_map(|element| {
+                serde_json::from_str::<JsonLdBook>(&element.text().collect::<String>()).ok()
+            })
+            .find(|book| book.kind == "Book")?;
+
+        let mut isbn10 = HashSet::new();
+        let mut isbn13 = HashSet::new();
+        match book.isbn.as_deref().map(Isbn::from_str) {
+            Some(Ok(Isbn::_10(isbn))) => {
+                isbn10.insert(isbn);
+            }
+            Some(Ok(Isbn::_13(isbn))) => {
+                isbn13.insert(isbn);
+            }
+            _ => {}
+        }
+
+        let mut cover_image = CoverImage::default();
+        if let Some(src) = &book.image {
+            insert_cover(&mut cover_image, src);
+        }
+
+        let (average_rating, ratings_count) = match book.aggregate_rating {
+            Some(rating) => (
+                // a number in the JSON, kept as shown like the rating of other sources
+                translater::optional_to_hashset(rating.rating_value.map(|v| v.to_string())),
+                translater::optional_to_hashset(rating.rating_count),
+            ),
+            None => (HashSet::new(), HashSet::new()),
+        };
+
+        let authors = match book.author {
+            JsonLdAuthors::One(author) => vec![author],
+            JsonLdAuthors::Many(authors) => authors,
+        };
+
+        Some(Metadata {
+            isbn10,
+            isbn13,
+            title: translater::title(&book.name),
+            series: translater::series(&book.name),
+            author: translater::authors(authors.iter().map(|author| author.name.as_str())),
+            page_count: translater::number(book.number_of_pages),
+            language: translater::language(book.in_language),
+            cover_image,
+            average_rating,
+            ratings_count,
+            ..Metadata::default()
+        })
+    }
+
+    /// Scrapes the older book details page layout with CSS selectors
+    fn from_selectors(page: &Html) -> Metadata {
         let mut title = HashSet::new();
-        for element in page.select(&title_selector) {
+        for element in page.select(&TITLE_SELECTOR) {
             title.insert(
                 element
                     .inner_html()
@@ -26,28 +220,40 @@ impl Goodreads {
             );
         }
 
-        let author_selector = Selector::parse(r#"a.authorName span[itemprop="name"]"#).unwrap();
+        // Rendered below the title as e.g. "(The Stormlight Archive #1)"
+        let mut series = HashSet::new();
+        for element in page.select(&SERIES_SELECTOR) {
+            let name = element
+                .text()
+                .collect::<String>()
+                .trim()
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .trim()
+                .to_owned();
+            if !name.is_empty() {
+                series.insert(name);
+            }
+        }
+
         let mut author = HashSet::new();
-        for element in page.select(&author_selector) {
+        for element in page.select(&AUTHOR_SELECTOR) {
             author.insert(element.inner_html());
         }
 
-        let tag_selector = Selector::parse("a.actionLinkLite.bookPageGenreLink").unwrap();
         let mut tag = HashSet::new();
-        for element in page.select(&tag_selector) {
-            tag.insert(element.inner_html());
+        for element in page.select(&TAG_SELECTOR) {
+            tag.extend(translater::tags(&element.inner_html()));
         }
 
-        let language_selector = Selector::parse(r#"div[itemprop="inLanguage"]"#).unwrap();
         let mut language = HashSet::new();
-        for element in page.select(&language_selector) {
-            language.insert(element.inner_html());
+        for element in page.select(&LANGUAGE_SELECTOR) {
+            language.insert(translater::normalize_language(&element.inner_html()));
         }
 
-        let isbn_selector = Selector::parse(r#"span[itemprop="isbn"]"#).unwrap();
         let mut isbn_10 = HashSet::new();
         let mut isbn_13 = HashSet::new();
-        for element in page.select(&isbn_selector) {
+        for element in page.select(&ISBN_SELECTOR) {
             let isbn = element.inner_html();
 
             if isbn.len() == 13 {
@@ -61,36 +267,51 @@ impl Goodreads {
         let isbn10 = isbn_10.into_iter().flatten().collect::<HashSet<_>>();
         let isbn13 = isbn_13.into_iter().flatten().collect::<HashSet<_>>();
 
-        let description_selector =
-            Selector::parse(r#"div#description span[style="display:none"]"#).unwrap();
         let mut description = HashSet::new();
-        for element in page.select(&description_selector) {
+        for element in page.select(&DESCRIPTION_SELECTOR) {
             description.insert(element.inner_html());
         }
 
-        let cover_image_selector = Selector::parse("img#coverImage").unwrap();
-        let mut cover_image = HashSet::new();
-        for element in page.select(&cover_image_selector) {
-            cover_image.insert(element.value().attr("src"));
+        // `img#coverImage` is the cover shown on the book details page,
+        // usually a resized thumbnail of the original image
+        let mut cover_image = CoverImage::default();
+        for element in page.select(&COVER_IMAGE_SELECTOR) {
+            if let Some(src) = element.value().attr("src") {
+                insert_cover(&mut cover_image, src);
+            }
+        }
+
+        let mut average_rating = HashSet::new();
+        for element in page.select(&AVERAGE_RATING_SELECTOR) {
+            let rating = element.text().collect::<String>().trim().to_owned();
+            if rating.parse::<f32>().is_ok() {
+                average_rating.insert(rating);
+            }
+        }
+
+        // Rendered as `<meta itemprop="ratingCount" content="12345">` followed by
+        // a human readable count such as "12,345 ratings"
+        let mut ratings_count = HashSet::new();
+        for element in page.select(&RATINGS_COUNT_SELECTOR) {
+            let count = element
+                .value()
+                .attr("content")
+                .map(str::to_owned)
+                .unwrap_or_else(|| element.text().collect::<String>())
+                .chars()
+                .filter(|c| c.is_ascii_digit())
+                .collect::<String>()
+                .parse::<u32>()
+                .ok();
+            ratings_count.extend(count);
         }
-        // TODO: Fix fallback
-        let cover_image = CoverImage {
-            thumbnail:       HashSet::default(),
-            small_thumbnail: HashSet::default(),
-            small:           HashSet::default(),
-            medium:          HashSet::default(),
-            large:           HashSet::default(),
-            extra_large:     HashSet::default(),
-        };
 
-        let page_count_selector = Selector::parse(r#"span[itemprop="numberOfPages"]"#).unwrap();
         let mut page_count = HashSet::new();
-        for element in page.select(&page_count_selector) {
+        for element in page.select(&PAGE_COUNT_SELECTOR) {
             let page_count_parse = element
                 .inner_html()
                 .chars()
-                .into_iter()
-                .filter(|c| c.is_digit(10))
+                .filter(|c| c.is_ascii_digit())
                 .collect::<String>()
                 .parse::<u16>()
                 .ok();
@@ -101,49 +322,172 @@ impl Goodreads {
         Metadata {
             isbn10,
             isbn13,
+            oclc: translater::empty(),
+            identifiers: HashMap::new(),
             title,
-            author,
+            series,
+            author: translater::authors(author),
             description,
             page_count,
             language,
             tag,
             cover_image,
+            average_rating,
+            ratings_count,
+            subtitle: HashSet::new(),
             publisher: HashSet::new(),
             publication_date: HashSet::new(),
+            table_of_contents: Vec::new(),
         }
     }
 }
 
 impl Goodreads {
-    /// Performs an ISBN search using Goodreads search
-    pub async fn from_isbn(isbn: &isbn2::Isbn) -> Result<Metadata, ReconError> {
+    /// Performs an ISBN search using Goodreads search,
+    /// failing with [`ReconError::NotFound`] if it lists no book.
+    pub async fn from_isbn(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &isbn2::Isbn,
+    ) -> Result<Metadata, ReconError> {
         let req = format!(
-            "https://www.goodreads.com/search?q={}&search[source]=goodreads&search_type=books&tab=books",
+            "{}/search?q={}&search[source]=goodreads&search_type=books&tab=books",
+            config.api_base_url(&Source::Goodreads),
             urlencoding::encode(&isbn.to_string())
         );
 
         debug!("ISBN: {:#?}", &isbn);
         debug!("Request: {:#?}", &req);
 
-        let response = reqwest::get(req)
-            .await
-            .map_err(ReconError::Connection)?
+        let response = fetch(client, config, &req)
+            .await?
             .text()
             .await
-            .map_err(ReconError::Connection)?;
+            .map_err(ReconError::connection)?;
 
         debug!("Response: {:#?}", &response);
 
+        // `Html` is not `Send`, parse without holding it across an `.await`
         let page = Html::parse_fragment(&response);
 
-        Ok(Self::from_web_page(&page).await)
+        // a found book redirects to its details page, otherwise the search page is served
+        if Self::is_empty_search(&page) {
+            return Err(ReconError::NotFound(isbn.clone()));
+        }
+
+        translater::with_config(config, || Self::from_web_page(&page))
+    }
+
+    /// Returns `true` for a search results page listing no book, e.g.
+    /// <https://www.goodreads.com/search?q=0000000000000>
+    fn is_empty_search(page: &Html) -> bool {
+        page.select(&SEARCH_HEADER_SELECTOR).next().is_some()
+            && page.select(&BOOK_TITLE_SELECTOR).next().is_none()
+    }
+
+    /// Extracts book details page URLs from a `Goodreads` search results page
+    /// This is an example of a search results page:
+    /// <https://www.goodreads.com/search?q=the+way+of+kings&search_type=books>
+    /// Links are relative and resolved against `base_url`.
+    pub fn book_urls(page: &Html, base_url: &str) -> Vec<String> {
+        page.select(&BOOK_TITLE_SELECTOR)
+            .filter_map(|element| element.value().attr("href"))
+            .map(|href| match href.split_once('?') {
+                Some((path, _)) => path, // dropping `?from_search=true` and friends
+                None => href,
+            })
+            .map(|path| format!("{}{}", base_url, path))
+            .collect()
+    }
+
+    /// Fetches a book details page and returns the first ISBN found on it,
+    /// preferring ISBN13 over ISBN10.
+    async fn isbn_from_book_url(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        url: &str,
+    ) -> Result<Option<Isbn>, ReconError> {
+        debug!("Request: {:#?}", &url);
+
+        let response = fetch(client, config, url)
+            .await?
+            .text()
+            .await
+            .map_err(ReconError::connection)?;
+
+        let metadata = Self::from_web_page(&Html::parse_document(&response))?;
+
+        let isbn13 = metadata.isbn13.into_iter().next().map(Isbn::_13);
+        let isbn10 = metadata.isbn10.into_iter().next().map(Isbn::_10);
+
+        Ok(isbn13.or(isbn10))
     }
 
     /// Performs a descriptive search using Goodreads search
-    pub async fn from_description(_description: &str) -> Result<Vec<Isbn>, ReconError> {
-        Err(ReconError::Message(
-            "Goodreads cannot be a search source currently.".to_owned(),
-        ))
+    /// Goodreads search results do not list ISBNs,
+    /// so each result's book details page is fetched to find one.
+    pub async fn from_description(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        description: &str,
+    ) -> Result<Vec<Isbn>, ReconError> {
+        let req = format!(
+            "{}/search?q={}&search_type=books",
+            config.api_base_url(&Source::Goodreads),
+            urlencoding::encode(description)
+        );
+
+        debug!("Description: {:#?}", &description);
+        debug!("Request: {:#?}", &req);
+
+        let response = fetch(client, config, &req)
+            .await?
+            .text()
+            .await
+            .map_err(ReconError::connection)?;
+
+        debug!("Response: {:#?}", &response);
+
+        let mut book_urls = Self::book_urls(
+            &Html::parse_document(&response),
+            config.api_base_url(&Source::Goodreads),
+        );
+
+        book_urls.truncate(config.description_limit);
+
+        let futures_list = book_urls
+            .iter()
+            .map(|url| Self::isbn_from_book_url(client, config, url))
+            .collect::<Vec<_>>();
+
+        let isbn_list = join_all(futures_list)
+            .await
+            .into_iter()
+            .flatten() // discarding `Err`
+            .flatten() // discarding pages without an ISBN
+            .collect::<Vec<_>>();
+
+        Ok(isbn_list)
+    }
+}
+
+impl BookSource for Goodreads {
+    fn from_isbn<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>> {
+        Box::pin(Self::from_isbn(client, config, isbn))
+    }
+
+    fn from_description<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(Self::from_description(client, config, description))
     }
 }
 
@@ -156,6 +500,7 @@ mod test {
     #[tokio::test]
     async fn parses_from_isbn() {
         use super::Goodreads;
+        use crate::recon::ReconConfig;
         use isbn2::Isbn;
         use log::debug;
         use std::str::FromStr;
@@ -163,7 +508,8 @@ mod test {
         init_logger();
 
         let isbn = Isbn::from_str("9781534431003").unwrap();
-        let resp = Goodreads::from_isbn(&isbn).await;
+        let config = ReconConfig::default();
+        let resp = Goodreads::from_isbn(&config.client().unwrap(), &config, &isbn).await;
         debug!("Response: {:#?}", resp);
         println!("Response: {:#?}", resp);
         assert!(resp.is_ok())
@@ -172,12 +518,244 @@ mod test {
     #[tokio::test]
     async fn parses_from_description() {
         use super::Goodreads;
+        use crate::recon::ReconConfig;
 
         init_logger();
 
         let description = "The way of kings";
-        let resp = Goodreads::from_description(description).await;
+        let config = ReconConfig::default();
+        let resp =
+            Goodreads::from_description(&config.client().unwrap(), &config, description).await;
         println!("Response: {:#?}", resp);
-        assert!(resp.is_err())
+        assert!(resp.is_ok())
+    }
+
+    #[test]
+    fn compiles_selectors() {
+        use super::*;
+
+        for selector in [
+            &TITLE_SELECTOR,
+            &SERIES_SELECTOR,
+            &AUTHOR_SELECTOR,
+            &TAG_SELECTOR,
+            &LANGUAGE_SELECTOR,
+            &ISBN_SELECTOR,
+            &DESCRIPTION_SELECTOR,
+            &COVER_IMAGE_SELECTOR,
+            &AVERAGE_RATING_SELECTOR,
+            &RATINGS_COUNT_SELECTOR,
+            &PAGE_COUNT_SELECTOR,
+            &JSON_LD_SELECTOR,
+            &BOOK_TITLE_SELECTOR,
+        ] {
+            Lazy::force(selector);
+        }
+    }
+
+    #[test]
+    fn parses_cover_image() {
+        use super::Goodreads;
+        use scraper::Html;
+
+        init_logger();
+
+        let page = Html::parse_document(
+            r#"
+            <h1 id="bookTitle">This Is How You Lose the Time War</h1>
+            <img id="coverImage" src="https://i.gr-assets.com/images/53870787.jpg">
+            "#,
+        );
+
+        let metadata = Goodreads::from_web_page(&page).unwrap();
+        assert!(metadata.series.is_empty());
+        assert!(metadata
+            .cover_image
+            .large
+            .contains("https://i.gr-assets.com/images/53870787.jpg"));
+    }
+
+    #[test]
+    fn upgrades_cover_thumbnail() {
+        use super::{full_size_cover, Goodreads};
+        use scraper::Html;
+
+        init_logger();
+
+        let base = "https://i.gr-assets.com/images/S/compressed.photo.goodreads.com/books/1551121541l/43352954";
+        let full_size = format!("{}.jpg", base);
+
+        assert_eq!(
+            full_size_cover(&format!("{}._SX98_.jpg", base)),
+            Some(full_size.clone())
+        );
+        assert_eq!(
+            full_size_cover(&format!("{}._SX318_SY475_.jpg", base)),
+            Some(full_size.clone())
+        );
+        assert_eq!(full_size_cover(&full_size), None);
+        assert_eq!(full_size_cover(&format!("{}._AB12_.jpg", base)), None);
+
+        let page = Html::parse_document(&format!(
+            r#"<img id="coverImage" src="{}._SY475_.jpg">"#,
+            base
+        ));
+
+        let metadata = Goodreads::from_web_page(&page).unwrap();
+        assert!(metadata.cover_image.extra_large.contains(&full_size));
+        assert!(metadata
+            .cover_image
+            .thumbnail
+            .contains(&format!("{}._SY475_.jpg", base)));
+    }
+
+    #[test]
+    fn parses_series() {
+        use super::Goodreads;
+        use scraper::Html;
+
+        init_logger();
+
+        let page = Html::parse_document(
+            r#"
+            <h1 id="bookTitle">The Way of Kings</h1>
+            <h2 id="bookSeries"><a href="/series/49075-the-stormlight-archive">
+              (The Stormlight Archive #1)
+            </a></h2>
+            "#,
+        );
+
+        let metadata = Goodreads::from_web_page(&page).unwrap();
+        assert!(metadata.series.contains("The Stormlight Archive #1"));
+    }
+
+    #[test]
+    fn parses_ratings() {
+        use super::Goodreads;
+        use scraper::Html;
+
+        init_logger();
+
+        let page = Html::parse_document(
+            r#"
+            <div id="bookMeta">
+              <span itemprop="ratingValue">
+                4.32
+              </span>
+              <meta itemprop="ratingCount" content="53604">
+              53,604 ratings
+            </div>
+            "#,
+        );
+
+        let metadata = Goodreads::from_web_page(&page).unwrap();
+        assert!(metadata.average_rating.contains("4.32"));
+        assert!(metadata.ratings_count.contains(&53604));
+    }
+
+    #[test]
+    fn parses_json_ld() {
+        use super::Goodreads;
+        use scraper::Html;
+
+        init_logger();
+
+        let page = Html::parse_document(
+            r#"
+            <script type="application/ld+json">{"@context":"https://schema.org","@type":"Book",
+              "name":"The Way of Kings (The Stormlight Archive #1)",
+              "image":"https://images-na.ssl-images-amazon.com/images/S/compressed.photo.goodreads.com/books/1659905828i/7235533.jpg",
+              "bookFormat":"Hardcover","numberOfPages":1007,"inLanguage":"English",
+              "isbn":"9780765326355",
+              "author":[{"@type":"Person","name":"Brandon Sanderson","url":"https://www.goodreads.com/author/show/38550"}],
+              "aggregateRating":{"@type":"AggregateRating","ratingValue":4.65,"ratingCount":516338,"reviewCount":31234}}
+            </script>
+            <h1 data-testid="bookTitle">The Way of Kings</h1>
+            "#,
+        );
+
+        let metadata = Goodreads::from_web_page(&page).unwrap();
+        assert!(metadata.title.contains("The Way of Kings"));
+        assert!(metadata.series.contains("The Stormlight Archive #1"));
+        assert!(metadata.author.contains("Brandon Sanderson"));
+        assert_eq!(metadata.isbn13.len(), 1);
+        assert!(metadata.page_count.contains(&1007));
+        assert!(metadata.language.contains("en"));
+        assert!(metadata.average_rating.contains("4.65"));
+        assert!(metadata.ratings_count.contains(&516338));
+        assert_eq!(metadata.cover_image.large.len(), 1);
+    }
+
+    #[test]
+    fn rejects_unrecognized_layout() {
+        use super::Goodreads;
+        use crate::recon::ReconError;
+        use scraper::Html;
+
+        init_logger();
+
+        let page = Html::parse_document(r#"<h1 data-testid="bookTitle">Dry</h1>"#);
+
+        assert!(matches!(
+            Goodreads::from_web_page(&page),
+            Err(ReconError::Message(_))
+        ));
+    }
+
+    #[test]
+    fn detects_empty_search() {
+        use super::Goodreads;
+        use scraper::Html;
+
+        init_logger();
+
+        let empty = Html::parse_document(
+            r#"
+            <form action="/search" method="get"><input name="q" value="0000000000000"></form>
+            <h3 class="searchSubNavContainer">No results.</h3>
+            "#,
+        );
+        assert!(Goodreads::is_empty_search(&empty));
+
+        let results = Html::parse_document(
+            r#"
+            <h3 class="searchSubNavContainer">Page 1 of about 2 results</h3>
+            <table class="tableList">
+              <tr><td><a class="bookTitle" href="/book/show/38740901-dry">Dry</a></td></tr>
+            </table>
+            "#,
+        );
+        assert!(!Goodreads::is_empty_search(&results));
+
+        // a book details page, or a layout that is not recognized
+        let page = Html::parse_document(r#"<h1 data-testid="bookTitle">Dry</h1>"#);
+        assert!(!Goodreads::is_empty_search(&page));
+    }
+
+    #[test]
+    fn parses_book_urls() {
+        use super::Goodreads;
+        use scraper::Html;
+
+        init_logger();
+
+        let page = Html::parse_document(
+            r#"
+            <table class="tableList">
+              <tr><td><a class="bookTitle" href="/book/show/7235533-the-way-of-kings?from_search=true&amp;qid=1">
+                <span>The Way of Kings</span></a></td></tr>
+              <tr><td><a class="bookTitle" href="/book/show/17332218-words-of-radiance">
+                <span>Words of Radiance</span></a></td></tr>
+            </table>
+            "#,
+        );
+
+        assert_eq!(
+            Goodreads::book_urls(&page, "https://www.goodreads.com"),
+            vec![
+                "https://www.goodreads.com/book/show/7235533-the-way-of-kings",
+                "https://www.goodreads.com/book/show/17332218-words-of-radiance",
+            ]
+        );
     }
 }
@@ -1,6 +1,8 @@
 use crate::metadata::Metadata;
-use crate::recon::ReconError;
+use crate::recon::{ReconConfig, ReconError, Source};
+use crate::source::{fetch, isbn_forms, BookSource, IsbnsWithTotal};
 use crate::util::translater;
+use futures::future::BoxFuture;
 use isbn2::Isbn;
 use log::debug;
 use serde::de;
@@ -10,7 +12,24 @@ use std::fmt;
 use std::marker::PhantomData;
 use std::str::FromStr;
 
-#[derive(Debug)]
+/// `volumeInfo` fields read by the `Deserialize` impl, also requested by ISBN searches
+/// as others are left out of their responses
+const FIELDS: &[&str] = &[
+    "industryIdentifiers",
+    "title",
+    "subtitle",
+    "authors",
+    "description",
+    "pageCount",
+    "publisher",
+    "publishedDate",
+    "categories",
+    "imageLinks",
+    "language",
+    "tableOfContents",
+];
+
+#[derive(Debug, Default)]
 /// A wrapper around [`Metadata`] for deserialization
 pub struct GoogleBooks(Metadata);
 
@@ -22,6 +41,7 @@ impl<'de> Deserialize<'de> for GoogleBooks {
         enum Field {
             IndustryIdentifiers,
             Title,
+            Subtitle,
             Authors,
             Description,
             PageCount,
@@ -30,6 +50,7 @@ impl<'de> Deserialize<'de> for GoogleBooks {
             Categories,
             ImageLinks,
             Language,
+            TableOfContents,
             Ignore,
         }
         struct FieldVisitor;
@@ -45,6 +66,7 @@ impl<'de> Deserialize<'de> for GoogleBooks {
                 match value {
                     "industryIdentifiers" => Ok(Field::IndustryIdentifiers),
                     "title" => Ok(Field::Title),
+                    "subtitle" => Ok(Field::Subtitle),
                     "authors" => Ok(Field::Authors),
                     "description" => Ok(Field::Description),
                     "pageCount" => Ok(Field::PageCount),
@@ -53,6 +75,7 @@ impl<'de> Deserialize<'de> for GoogleBooks {
                     "categories" => Ok(Field::Categories),
                     "imageLinks" => Ok(Field::ImageLinks),
                     "language" => Ok(Field::Language),
+                    "tableOfContents" => Ok(Field::TableOfContents),
                     _ => Ok(Field::Ignore),
                 }
             }
@@ -83,7 +106,8 @@ impl<'de> Deserialize<'de> for GoogleBooks {
             {
                 let mut industry_identifiers = None;
                 let mut title = None;
-                let mut authors = None;
+                let mut subtitle = None;
+                let mut authors: Option<Vec<&str>> = None;
                 let mut description = None;
                 let mut page_count = None;
                 let mut publisher = None;
@@ -91,6 +115,7 @@ impl<'de> Deserialize<'de> for GoogleBooks {
                 let mut categories = None;
                 let mut image_links = None;
                 let mut language = None;
+                let mut table_of_contents = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -106,6 +131,12 @@ impl<'de> Deserialize<'de> for GoogleBooks {
                             }
                             title = Some(map.next_value()?);
                         }
+                        Field::Subtitle => {
+                            if subtitle.is_some() {
+                                return Err(de::Error::duplicate_field("subtitle"));
+                            }
+                            subtitle = Some(map.next_value()?);
+                        }
                         Field::Authors => {
                             if authors.is_some() {
                                 return Err(de::Error::duplicate_field("authors"));
@@ -154,6 +185,12 @@ impl<'de> Deserialize<'de> for GoogleBooks {
                             }
                             language = Some(map.next_value()?);
                         }
+                        Field::TableOfContents => {
+                            if table_of_contents.is_some() {
+                                return Err(de::Error::duplicate_field("tableOfContents"));
+                            }
+                            table_of_contents = Some(map.next_value()?);
+                        }
                         _ => {
                             let _ = match A::next_value::<de::IgnoredAny>(&mut map) {
                                 Ok(val) => val,
@@ -166,32 +203,27 @@ impl<'de> Deserialize<'de> for GoogleBooks {
                 }
 
                 Ok(GoogleBooks(Metadata {
-                    isbn10:           translater::googlebooks_isbn10(&industry_identifiers),
-                    isbn13:           translater::googlebooks_isbn13(&industry_identifiers),
-                    title:            translater::string(title),
-                    author:           translater::vec(authors),
-                    description:      translater::string(description),
-                    page_count:       translater::number(page_count),
-                    publisher:        translater::string(publisher),
-                    publication_date: translater::publication_date(published_date),
-                    language:         translater::string(language),
-                    tag:              translater::vec(categories),
-                    cover_image:      translater::googlebooks_cover_images(image_links),
+                    isbn10:            translater::googlebooks_isbn10(&industry_identifiers),
+                    isbn13:            translater::googlebooks_isbn13(&industry_identifiers),
+                    oclc:              translater::googlebooks_oclc(&industry_identifiers),
+                    identifiers:       translater::googlebooks_identifiers(&industry_identifiers),
+                    title:             translater::title(&title),
+                    subtitle:          translater::string(subtitle),
+                    series:            translater::series(&title),
+                    author:            translater::authors(authors.unwrap_or_default()),
+                    description:       translater::html(description),
+                    page_count:        translater::number(page_count),
+                    publisher:         translater::string(publisher),
+                    publication_date:  translater::publication_date(published_date),
+                    language:          translater::language(language),
+                    tag:               translater::vec_split(categories),
+                    cover_image:       translater::googlebooks_cover_images(image_links),
+                    average_rating:    translater::empty(),
+                    ratings_count:     translater::empty(),
+                    table_of_contents: translater::table_of_contents(table_of_contents),
                 }))
             }
         }
-        const FIELDS: &[&str] = &[
-            "industryIdentifiers",
-            "title",
-            "authors",
-            "description",
-            "pageCount",
-            "publisher",
-            "publishedDate",
-            "categories",
-            "imageLinks",
-            "language",
-        ];
         Deserializer::deserialize_struct(
             deserializer,
             "GoogleBooks",
@@ -205,19 +237,83 @@ impl<'de> Deserialize<'de> for GoogleBooks {
 }
 
 impl GoogleBooks {
+    /// Query parameters shared by every request,
+    /// the API key and country from [`ReconConfig`] if configured
+    fn query_params(config: &ReconConfig) -> String {
+        let mut params = String::new();
+
+        if let Some(key) = &config.google_books_api_key {
+            params.push_str(&format!("&key={}", urlencoding::encode(key)));
+        }
+
+        if let Some(country) = &config.google_books_country {
+            params.push_str(&format!("&country={}", urlencoding::encode(country)));
+        }
+
+        params
+    }
+
     /// Performs an ISBN search using GoogleBooks API
     /// <https://developers.google.com/books/docs/v1/using>
-    pub async fn from_isbn(isbn: &isbn2::Isbn) -> Result<Metadata, ReconError> {
+    pub async fn from_isbn(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &isbn2::Isbn,
+    ) -> Result<Metadata, ReconError> {
+        Self::from_isbn_raw(client, config, isbn)
+            .await
+            .map(|(metadata, _)| metadata)
+    }
+
+    /// Same as [`GoogleBooks::from_isbn`] but the response body is returned
+    /// alongside the parsed [`Metadata`]
+    pub async fn from_isbn_raw(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &isbn2::Isbn,
+    ) -> Result<(Metadata, serde_json::Value), ReconError> {
+        // searched by the canonical ISBN-13 whichever form was given
         let req = format!(
-            "https://www.googleapis.com/books/v1/volumes?q=isbn:{}&fields=items/volumeInfo(title,authors,publisher,publishedDate,language,industryIdentifiers,description,categories,imageLinks)&maxResults=1",
-            urlencoding::encode(&isbn.to_string())
+            "{}/volumes?q=isbn:{}&fields=items/volumeInfo({})&maxResults=1{}",
+            config.api_base_url(&Source::GoogleBooks),
+            urlencoding::encode(&isbn_forms(isbn)[0]),
+            FIELDS.join(","),
+            Self::query_params(config)
         );
 
         debug!("ISBN: {:#?}", &isbn);
         debug!("Request: {:#?}", &req);
 
+        let response = fetch(client, config, &req)
+            .await?
+            .json::<serde_json::Value>()
+            .await
+            .map_err(ReconError::connection)?;
+
+        debug!("Response: {:#?}", &response);
+
+        let metadata =
+            translater::with_config(config, || Self::from_isbn_response(isbn, &response))?;
+
+        Ok((metadata, response))
+    }
+
+    /// Parses [`Metadata`] from a pre-fetched `volumeInfo` object of a volume,
+    /// without making a request
+    pub fn from_value(value: &serde_json::Value) -> Result<Metadata, ReconError> {
+        GoogleBooks::deserialize(value)
+            .map(|volume_info| volume_info.0)
+            .map_err(ReconError::JSONParse)
+    }
+
+    /// Parses [`Metadata`] from the response body of an ISBN search
+    fn from_isbn_response(
+        isbn: &Isbn,
+        response: &serde_json::Value,
+    ) -> Result<Metadata, ReconError> {
         #[derive(Debug, Deserialize)]
         struct Items {
+            #[serde(default)] // omitted if there are no results
             items: Vec<VolumeInfo>,
         }
 
@@ -227,33 +323,90 @@ impl GoogleBooks {
             volume_info: GoogleBooks,
         }
 
-        let response = reqwest::get(req)
-            .await
-            .map_err(ReconError::Connection)?
-            .json::<Items>()
-            .await
-            .map_err(ReconError::Connection)?;
-
-        debug!("Response: {:#?}", &response);
+        let items = Items::deserialize(response).map_err(ReconError::JSONParse)?;
 
-        let metadata = response.items.into_iter().map(|v| v.volume_info.0).next();
+        // merging every volume returned rather than an arbitrary one
+        let metadata = items
+            .items
+            .into_iter()
+            .map(|v| v.volume_info.0)
+            .reduce(|merged, m| merged + m);
 
-        Ok(metadata.unwrap_or_default())
+        metadata.ok_or_else(|| ReconError::NotFound(isbn.clone()))
     }
 
     /// Performs a descriptive search using GoogleBooks API
     /// <https://developers.google.com/books/docs/v1/using>
-    pub async fn from_description(description: &str) -> Result<Vec<Isbn>, ReconError> {
-        let req = format!(
-            "https://www.googleapis.com/books/v1/volumes?q={}&fields=items/volumeInfo(industryIdentifiers)&maxResults=3",
-            urlencoding::encode(description)
-        );
+    pub async fn from_description(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        description: &str,
+    ) -> Result<Vec<Isbn>, ReconError> {
+        Self::from_description_with_total(client, config, description)
+            .await
+            .map(|(isbns, _)| isbns)
+    }
 
+    /// Same as [`GoogleBooks::from_description`], also returning the `totalItems` reported
+    pub async fn from_description_with_total(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        description: &str,
+    ) -> Result<IsbnsWithTotal, ReconError> {
         debug!("Description: {:#?}", &description);
+
+        Self::search(client, config, &urlencoding::encode(description)).await
+    }
+
+    /// Performs a search restricted to the `intitle:` and `inauthor:` keywords of GoogleBooks API
+    /// <https://developers.google.com/books/docs/v1/using#PerformingSearch>
+    pub async fn from_title_author(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        title: &str,
+        author: &str,
+    ) -> Result<Vec<Isbn>, ReconError> {
+        debug!("Title: {:#?}, Author: {:#?}", &title, &author);
+
+        let query = format!(
+            "intitle:{}+inauthor:{}",
+            urlencoding::encode(title),
+            urlencoding::encode(author)
+        );
+
+        Self::search(client, config, &query)
+            .await
+            .map(|(isbns, _)| isbns)
+    }
+
+    /// Returns an ISBN from each volume matching `query`, which must already be URL encoded,
+    /// and the total number of matching volumes
+    async fn search(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        query: &str,
+    ) -> Result<IsbnsWithTotal, ReconError> {
+        let mut req = format!(
+            "{}/volumes?q={}&fields=totalItems,items/volumeInfo(industryIdentifiers)&startIndex={}&maxResults={}{}",
+            config.api_base_url(&Source::GoogleBooks),
+            query,
+            config.description_offset,
+            config.description_limit.clamp(1, 40), // bounds accepted by the API
+            Self::query_params(config)
+        );
+
+        // only searches are restricted, an ISBN identifies a single edition already
+        if let Some(language) = &config.preferred_language {
+            req.push_str(&format!("&langRestrict={}", urlencoding::encode(language)));
+        }
+
         debug!("Request: {:#?}", &req);
 
         #[derive(Debug, Deserialize)]
         struct Items {
+            #[serde(rename = "totalItems")]
+            total_items: Option<usize>,
+            #[serde(default)] // omitted if there are no results
             items: Vec<VolumeInfo>,
         }
 
@@ -266,15 +419,14 @@ impl GoogleBooks {
         #[derive(Debug, Deserialize)]
         struct IndustryIdentifiers {
             #[serde(rename = "industryIdentifiers")]
-            industry_identifiers: Vec<HashMap<String, String>>,
+            industry_identifiers: Option<Vec<HashMap<String, String>>>,
         }
 
-        let response = reqwest::get(req)
-            .await
-            .map_err(ReconError::Connection)?
+        let response = fetch(client, config, &req)
+            .await?
             .json::<Items>()
             .await
-            .map_err(ReconError::Connection)?;
+            .map_err(ReconError::connection)?;
 
         debug!("Response: {:#?}", &response);
 
@@ -282,15 +434,13 @@ impl GoogleBooks {
         let mut isbns: Vec<&String> = response
             .items
             .iter()
-            .map(
-                |info| info.volume_info.industry_identifiers.get(0), // first ISBN found
-            )
-            .map(|h| h.map(|h| h.get("identifier")))
-            .flatten()
-            .flatten()
+            // discarding volumes without identifiers
+            .filter_map(|info| info.volume_info.industry_identifiers.as_ref())
+            .filter_map(|identifiers| identifiers.first()) // first ISBN found
+            .filter_map(|h| h.get("identifier"))
             .collect::<Vec<_>>();
 
-        isbns.truncate(3); // first 3 results
+        isbns.truncate(config.description_limit);
 
         let mut isbn_list = Vec::new();
 
@@ -298,13 +448,52 @@ impl GoogleBooks {
             isbn_list.push(Isbn::from_str(isbn));
         }
 
-        let isbn_list = isbn_list
-            .into_iter()
-            .filter(|isbn| isbn.is_ok())
-            .map(|isbn| isbn.unwrap())
-            .collect::<Vec<_>>();
+        let isbn_list = isbn_list.into_iter().flatten().collect::<Vec<_>>();
+
+        Ok((isbn_list, response.total_items))
+    }
+}
+
+impl BookSource for GoogleBooks {
+    fn from_isbn<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>> {
+        Box::pin(Self::from_isbn(client, config, isbn))
+    }
 
-        Ok(isbn_list)
+    fn from_description<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(Self::from_description(client, config, description))
+    }
+
+    fn from_description_with_total<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<IsbnsWithTotal, ReconError>> {
+        Box::pin(Self::from_description_with_total(
+            client,
+            config,
+            description,
+        ))
+    }
+
+    fn from_title_author<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        title: &'a str,
+        author: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(Self::from_title_author(client, config, title, author))
     }
 }
 
@@ -317,6 +506,7 @@ mod test {
     #[tokio::test]
     async fn parses_from_isbn() {
         use super::GoogleBooks;
+        use crate::recon::ReconConfig;
         use isbn2::Isbn;
         use log::debug;
         use std::str::FromStr;
@@ -324,20 +514,166 @@ mod test {
         init_logger();
 
         let isbn = Isbn::from_str("9781534431003").unwrap();
-        let resp = GoogleBooks::from_isbn(&isbn).await;
+        let config = ReconConfig::default();
+        let resp = GoogleBooks::from_isbn(&config.client().unwrap(), &config, &isbn).await;
         debug!("Response: {:#?}", resp);
         assert!(resp.is_ok())
     }
 
+    #[test]
+    fn appends_query_params() {
+        use super::GoogleBooks;
+        use crate::recon::ReconConfig;
+
+        let config = ReconConfig {
+            google_books_api_key: Some("secret".to_owned()),
+            google_books_country: Some("DE".to_owned()),
+            ..ReconConfig::default()
+        };
+        assert_eq!(GoogleBooks::query_params(&config), "&key=secret&country=DE");
+
+        let config = ReconConfig {
+            google_books_api_key: None,
+            google_books_country: None,
+            ..ReconConfig::default()
+        };
+        assert_eq!(GoogleBooks::query_params(&config), "");
+    }
+
+    #[test]
+    fn parses_from_isbn_response() {
+        use super::GoogleBooks;
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        let response = serde_json::json!({
+            "items": [{
+                "volumeInfo": {
+                    "title": "This Is How You Lose the Time War",
+                    "subtitle": "A Novel",
+                    "authors": ["Amal El-Mohtar", "Max Gladstone"],
+                    "industryIdentifiers": [
+                        { "type": "ISBN_13", "identifier": "9781534431003" },
+                        { "type": "ISSN", "identifier": "1234-5678" },
+                        { "type": "OTHER", "identifier": "OCLC:1100424998" }
+                    ]
+                }
+            }, {
+                "volumeInfo": {
+                    "title": "This Is How You Lose the Time War",
+                    "publisher": "Saga Press"
+                }
+            }]
+        });
+
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let metadata = GoogleBooks::from_isbn_response(&isbn, &response).unwrap();
+        assert!(metadata.title.contains("This Is How You Lose the Time War"));
+        assert!(metadata.subtitle.contains("A Novel"));
+        assert!(metadata.publisher.contains("Saga Press"));
+        assert_eq!(metadata.author.len(), 2);
+        assert_eq!(metadata.isbn13.len(), 1);
+        assert!(metadata.oclc.contains("1100424998"));
+        assert!(metadata.identifiers["issn"].contains("1234-5678"));
+        assert!(!metadata.identifiers.contains_key("oclc"));
+    }
+
+    #[test]
+    fn parses_from_value() {
+        use super::GoogleBooks;
+
+        let volume_info = serde_json::json!({
+            "title": "This Is How You Lose the Time War",
+            "authors": ["Amal El-Mohtar", "Max Gladstone"],
+            "publishedDate": "2019-07-16"
+        });
+
+        let metadata = GoogleBooks::from_value(&volume_info).unwrap();
+        assert!(metadata.title.contains("This Is How You Lose the Time War"));
+        assert_eq!(metadata.author.len(), 2);
+        assert_eq!(metadata.publication_date.len(), 1);
+
+        assert!(GoogleBooks::from_value(&serde_json::json!({ "title": 1 })).is_err());
+    }
+
+    #[tokio::test]
+    async fn requests_parsed_fields() {
+        use super::GoogleBooks;
+        use crate::recon::{ReconConfig, Source};
+        use isbn2::Isbn;
+        use std::str::FromStr;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        init_logger();
+
+        // serves one response and hands back the request line it was asked with
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let body = r#"{ "items": [{ "volumeInfo": {
+                "title": "Dry",
+                "pageCount": 400,
+                "tableOfContents": [{ "level": 0, "title": "Part One" }]
+            } }] }"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf[..n])
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_owned()
+        });
+
+        let config = ReconConfig::new().base_url(Source::GoogleBooks, url);
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let metadata = GoogleBooks::from_isbn(&config.client().unwrap(), &config, &isbn)
+            .await
+            .unwrap();
+        assert!(metadata.page_count.contains(&400));
+        assert_eq!(metadata.table_of_contents, ["Part One"]);
+
+        // the API leaves fields missing from the filter out of its response
+        let request = server.await.unwrap();
+        assert!(request.contains("pageCount"), "{}", request);
+        assert!(request.contains("tableOfContents"), "{}", request);
+    }
+
+    #[test]
+    fn reports_isbn_not_found() {
+        use super::GoogleBooks;
+        use crate::recon::ReconError;
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let response = serde_json::json!({});
+
+        assert!(matches!(
+            GoogleBooks::from_isbn_response(&isbn, &response),
+            Err(ReconError::NotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn parses_from_description() {
         use super::GoogleBooks;
+        use crate::recon::ReconConfig;
         use log::debug;
 
         init_logger();
 
         let description = "This is how you lose the time war";
-        let resp = GoogleBooks::from_description(description).await;
+        let config = ReconConfig::default();
+        let resp =
+            GoogleBooks::from_description(&config.client().unwrap(), &config, description).await;
         debug!("Response: {:#?}", resp);
         assert!(resp.is_ok())
     }
@@ -0,0 +1,183 @@
+use crate::metadata::Metadata;
+use crate::recon::{ReconConfig, ReconError, Source};
+use crate::source::{fetch, BookSource};
+use crate::util::translater;
+use futures::future::BoxFuture;
+use isbn2::Isbn;
+use log::debug;
+
+#[derive(Debug)]
+/// Parses [`Metadata`] from the "Common Knowledge" of LibraryThing works
+pub struct LibraryThing;
+
+impl LibraryThing {
+    /// Performs an ISBN search using LibraryThing web services,
+    /// requires [`ReconConfig::library_thing_api_key`].
+    /// <https://www.librarything.com/services/webservices.php>
+    pub async fn from_isbn(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &Isbn,
+    ) -> Result<Metadata, ReconError> {
+        let key = config.library_thing_api_key.as_ref().ok_or_else(|| {
+            ReconError::Message(
+                "LibraryThing requires a developer key, set ReconConfig::library_thing_api_key."
+                    .to_owned(),
+            )
+        })?;
+
+        let req = format!(
+            "{}/?method=librarything.ck.getwork&isbn={}&apikey={}",
+            config.api_base_url(&Source::LibraryThing),
+            urlencoding::encode(&isbn.to_string()),
+            urlencoding::encode(key)
+        );
+
+        debug!("ISBN: {:#?}", &isbn);
+
+        let response = fetch(client, config, &req)
+            .await?
+            .text()
+            .await
+            .map_err(ReconError::connection)?;
+
+        debug!("Response: {:#?}", &response);
+
+        translater::with_config(config, || Self::from_response(isbn, &response))
+    }
+
+    /// Parses [`Metadata`] from the `ltml` response of `librarything.ck.getwork`.
+    ///
+    /// The work's `<author>` and the `canonicaltitle`, `series`,
+    /// `originalpublicationdate` and `tags` Common Knowledge fields are kept.
+    fn from_response(isbn: &Isbn, response: &str) -> Result<Metadata, ReconError> {
+        let document = roxmltree::Document::parse(response)
+            .map_err(|err| ReconError::Message(err.to_string()))?;
+
+        // `<response stat="fail"><err code="...">message</err></response>`
+        if let Some(err) = document.descendants().find(|node| node.has_tag_name("err")) {
+            return Err(ReconError::Message(format!(
+                "LibraryThing: {}",
+                err.text().unwrap_or_default()
+            )));
+        }
+
+        let item = document
+            .descendants()
+            .find(|node| node.has_tag_name("item"))
+            .ok_or_else(|| ReconError::NotFound(isbn.clone()))?;
+
+        let mut metadata = Metadata {
+            author: translater::authors(
+                item.children()
+                    .filter(|node| node.has_tag_name("author"))
+                    .filter_map(|node| node.text()),
+            ),
+            ..Metadata::default()
+        };
+
+        for field in item.descendants().filter(|node| node.has_tag_name("field")) {
+            let facts = field
+                .descendants()
+                .filter(|node| node.has_tag_name("fact"))
+                .filter_map(|node| node.text())
+                .map(str::trim)
+                .filter(|fact| !fact.is_empty());
+
+            match field.attribute("name") {
+                Some("canonicaltitle") => metadata.title.extend(facts.map(str::to_owned)),
+                Some("series") => metadata.series.extend(facts.map(str::to_owned)),
+                Some("originalpublicationdate") => metadata
+                    .publication_date
+                    .extend(facts.flat_map(|date| translater::publication_date(Some(date)))),
+                Some("tags") => metadata.tag.extend(facts.flat_map(translater::tags)),
+                _ => {}
+            }
+        }
+
+        match metadata.is_empty() {
+            true => Err(ReconError::NotFound(isbn.clone())),
+            false => Ok(metadata),
+        }
+    }
+}
+
+impl BookSource for LibraryThing {
+    fn from_isbn<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>> {
+        Box::pin(Self::from_isbn(client, config, isbn))
+    }
+
+    fn from_description<'a>(
+        &'a self,
+        _client: &'a reqwest::Client,
+        _config: &'a ReconConfig,
+        _description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(async move {
+            Err(ReconError::Message(
+                "LibraryThing has no descriptive search.".to_owned(),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LibraryThing;
+    use crate::recon::{ReconConfig, ReconError};
+    use isbn2::Isbn;
+    use std::str::FromStr;
+
+    #[test]
+    fn parses_from_response() {
+        let response = r#"<?xml version="1.0" encoding="UTF-8"?>
+<response stat="ok">
+  <ltml xmlns="http://www.librarything.com/" version="1.1">
+    <item id="21856781" type="work">
+      <author id="8457" authorcode="shustermanneal">Neal Shusterman</author>
+      <commonknowledge>
+        <fieldList>
+          <field type="42" name="canonicaltitle" displayName="Canonical title">
+            <versionList><version><factList><fact>Dry</fact></factList></version></versionList>
+          </field>
+          <field type="16" name="originalpublicationdate" displayName="Original publication date">
+            <versionList><version><factList><fact>2018-10-02</fact></factList></version></versionList>
+          </field>
+          <field type="3" name="series" displayName="Series">
+            <versionList><version><factList><fact><![CDATA[Dry (1)]]></fact></factList></version></versionList>
+          </field>
+        </fieldList>
+      </commonknowledge>
+    </item>
+  </ltml>
+</response>"#;
+
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let metadata = LibraryThing::from_response(&isbn, response).unwrap();
+        assert!(metadata.title.contains("Dry"));
+        assert!(metadata.author.contains("Neal Shusterman"));
+        assert!(metadata.series.contains("Dry (1)"));
+        assert_eq!(metadata.publication_date.len(), 1);
+
+        let failed = r#"<response stat="fail"><err code="101">Invalid API key</err></response>"#;
+        assert!(matches!(
+            LibraryThing::from_response(&isbn, failed),
+            Err(ReconError::Message(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn requires_api_key() {
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let config = ReconConfig::default();
+        let client = config.client().unwrap();
+
+        let res = LibraryThing::from_isbn(&client, &config, &isbn).await;
+        assert!(matches!(res, Err(ReconError::Message(_))));
+    }
+}
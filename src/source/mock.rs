@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use crate::metadata::Metadata;
+use crate::recon::{ReconConfig, ReconError};
+use crate::source::BookSource;
+use futures::future::BoxFuture;
+use isbn2::Isbn;
+
+/// Canned results served by [`Source::Mock`](crate::Source::Mock) without network access.
+///
+/// Fixtures are registered on a [`ReconConfig`] and looked up by the searches made with it.
+///
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use isbn2::Isbn;
+/// use recon_metadata::{Metadata, Mock, ReconConfig, Source};
+/// use std::str::FromStr;
+///
+/// let isbn = Isbn::from_str("9781534431003").unwrap();
+/// let config = ReconConfig {
+///     mock: Mock::default().isbn(&isbn, Metadata::default()),
+///     ..ReconConfig::default()
+/// };
+///
+/// let res = Metadata::from_isbn_with_config(&config, &[Source::Mock], &isbn).await;
+/// assert!(res.is_ok());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Mock {
+    /// Keyed by ISBN-13 so both forms of an ISBN find the same fixture
+    isbns:        HashMap<String, Metadata>,
+    descriptions: HashMap<String, Vec<Isbn>>,
+}
+
+/// Key of `isbn` in [`Mock::isbns`]
+fn key(isbn: &Isbn) -> String {
+    match isbn {
+        Isbn::_10(isbn) => isbn2::Isbn13::from(*isbn).to_string(),
+        Isbn::_13(isbn) => isbn.to_string(),
+    }
+}
+
+impl Mock {
+    /// Returns `metadata` for searches of `isbn`.
+    /// ISBN-10 and ISBN-13 are both filled in from `isbn` if missing.
+    pub fn isbn(mut self, isbn: &Isbn, mut metadata: Metadata) -> Self {
+        match isbn {
+            Isbn::_10(isbn) => metadata.isbn10.insert(*isbn),
+            Isbn::_13(isbn) => metadata.isbn13.insert(*isbn),
+        };
+        metadata.normalize_isbns();
+
+        self.isbns.insert(key(isbn), metadata);
+        self
+    }
+
+    /// Returns `isbns` for descriptive searches of exactly `description`.
+    pub fn description(mut self, description: &str, isbns: Vec<Isbn>) -> Self {
+        self.descriptions.insert(description.to_owned(), isbns);
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+/// A source serving the fixtures of [`ReconConfig::mock`]
+pub(crate) struct MockSource;
+
+impl BookSource for MockSource {
+    fn from_isbn<'a>(
+        &'a self,
+        _client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>> {
+        Box::pin(async move {
+            config
+                .mock
+                .isbns
+                .get(&key(isbn))
+                .cloned()
+                .ok_or_else(|| ReconError::NotFound(isbn.clone()))
+        })
+    }
+
+    fn from_description<'a>(
+        &'a self,
+        _client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(async move {
+            Ok(config
+                .mock
+                .descriptions
+                .get(description)
+                .cloned()
+                .unwrap_or_default())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Mock;
+    use crate::metadata::test::titled;
+    use crate::metadata::Metadata;
+    use crate::recon::{ReconConfig, ReconError, Source};
+    use isbn2::Isbn;
+    use std::str::FromStr;
+    use tokio_util::sync::CancellationToken;
+
+    #[tokio::test]
+    async fn serves_fixtures() {
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let missing = Isbn::from_str("9780765326355").unwrap();
+        let config = ReconConfig {
+            mock: Mock::default()
+                .isbn(&isbn, titled("Dry"))
+                .description("Neal Shusterman", vec![isbn.clone(), missing.clone()]),
+            ..ReconConfig::default()
+        };
+
+        let res = Metadata::from_isbn_with_config(&config, &[Source::Mock], &isbn)
+            .await
+            .unwrap();
+        assert!(res.title.contains("Dry"));
+        assert_eq!(res.isbn10.len(), 1);
+
+        let isbn10 = Isbn::from_str("1534431004").unwrap();
+        let res = Metadata::from_isbn_with_config(&config, &[Source::Mock], &isbn10).await;
+        assert!(res.is_ok());
+
+        let res = Metadata::from_isbn_with_config(&config, &[Source::Mock], &missing).await;
+        assert!(matches!(res, Err(ReconError::NotFound(_))));
+
+        let res = Metadata::from_description_with_config(
+            &config,
+            &Source::Mock,
+            &[Source::Mock],
+            "Neal Shusterman",
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.len(), 1);
+        assert!(res[0].title.contains("Dry"));
+
+        // without a reported total, every ISBN found counts
+        let res = Metadata::from_description_with_total(
+            &config,
+            &Source::Mock,
+            &[Source::Mock],
+            "Neal Shusterman",
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.total, 2);
+        assert_eq!(res.results.len(), 1);
+
+        let res = Metadata::from_description_with_isbns(
+            &config,
+            &Source::Mock,
+            &[Source::Mock],
+            "Neal Shusterman",
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].0.to_string(), isbn.to_string());
+        assert!(res[0].1.title.contains("Dry"));
+    }
+
+    #[tokio::test]
+    async fn ranks_preferred_language_first() {
+        let english = Isbn::from_str("9781534431003").unwrap();
+        let german = Isbn::from_str("9780765326355").unwrap();
+        let mut deutsch = titled("Der Weg der Könige");
+        deutsch.language.insert("de".to_owned());
+        let config = ReconConfig {
+            mock: Mock::default()
+                .isbn(&english, titled("The Way of Kings"))
+                .isbn(&german, deutsch)
+                .description("Sanderson", vec![english.clone(), german.clone()]),
+            ..ReconConfig::default()
+        }
+        .preferred_language("German");
+
+        let res = Metadata::from_description_with_config(
+            &config,
+            &Source::Mock,
+            &[Source::Mock],
+            "Sanderson",
+        )
+        .await
+        .unwrap();
+        assert!(res[0].title.contains("Der Weg der Könige"));
+        assert!(res[1].title.contains("The Way of Kings"));
+    }
+
+    #[tokio::test]
+    async fn stops_once_cancelled() {
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let config = ReconConfig {
+            mock: Mock::default()
+                .isbn(&isbn, titled("Dry"))
+                .description("Neal Shusterman", vec![isbn.clone()]),
+            ..ReconConfig::default()
+        };
+        let cancel = CancellationToken::new();
+
+        let res = Metadata::from_isbn_cancellable(&config, &[Source::Mock], &isbn, &cancel).await;
+        assert!(res.unwrap().title.contains("Dry"));
+
+        cancel.cancel();
+        let res = Metadata::from_isbn_cancellable(&config, &[Source::Mock], &isbn, &cancel).await;
+        assert!(matches!(res, Err(ReconError::Message(_))));
+
+        let res = Metadata::from_description_cancellable(
+            &config,
+            &Source::Mock,
+            &[Source::Mock],
+            "Neal Shusterman",
+            &cancel,
+        )
+        .await;
+        assert!(matches!(res, Err(ReconError::Message(_))));
+    }
+}
@@ -1,9 +1,529 @@
+use crate::metadata::Metadata;
+use crate::recon::{ReconConfig, ReconError, Source};
+use futures::future::BoxFuture;
+use isbn2::{Isbn, Isbn10, Isbn13};
+use log::debug;
+use rand::Rng;
+use reqwest::StatusCode;
+use std::time::{Duration, SystemTime};
+
+/// Amazon product page impl.
+/// <https://www.amazon.com/dp/{isbn10}>
+#[cfg(feature = "amazon")]
+pub(crate) mod amazon;
+/// Crossref REST API impl.
+/// <https://api.crossref.org/swagger-ui/index.html>
+#[cfg(feature = "crossref")]
+pub(crate) mod crossref;
+/// EPUB package document reader, a local source outside of [`Source`].
+/// <https://www.w3.org/TR/epub-33/#sec-package-doc>
+#[cfg(feature = "epub")]
+pub(crate) mod epub;
 /// Goodreads search impl.
 /// <https://www.goodreads.com/search?q={}&search[source]=goodreads&search_type=books&tab=books>
+#[cfg(feature = "goodreads")]
 pub(crate) mod goodreads;
 /// GoogleBooks API impl.
 /// <https://developers.google.com/books/docs/v1/using>
+#[cfg(feature = "google_books")]
 pub(crate) mod google_books;
+/// LibraryThing Common Knowledge impl.
+/// <https://www.librarything.com/services/rest/documentation/1.1/librarything.ck.getwork.php>
+#[cfg(feature = "library_thing")]
+pub(crate) mod librarything;
+/// Canned results for tests without network access.
+#[cfg(feature = "mock")]
+pub(crate) mod mock;
 /// OpenLibrary API impl.
 /// <https://openlibrary.org/developers/api>
+#[cfg(feature = "open_library")]
 pub(crate) mod open_library;
+/// PDF document information and XMP reader, a local source outside of [`Source`].
+/// <https://opensource.adobe.com/dc-acrobat-sdk-docs/pdfstandards/PDF32000_2008.pdf>
+#[cfg(feature = "pdf")]
+pub(crate) mod pdf;
+
+/// ISBNs found by a descriptive search and the total number of matches reported, if any
+pub(crate) type IsbnsWithTotal = (Vec<Isbn>, Option<usize>);
+
+/// A database or search provider that can be queried for books.
+///
+/// Each [`Source`] variant maps to one implementation through [`book_source`],
+/// adding a new source only requires an implementation and a registration there.
+#[allow(clippy::wrong_self_convention)]
+pub(crate) trait BookSource: Send + Sync {
+    /// Performs an ISBN search using a shared `client`
+    fn from_isbn<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>>;
+
+    /// Performs a descriptive search using a shared `client`
+    /// returning ISBNs of matching books
+    fn from_description<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>>;
+
+    /// Performs a descriptive search like [`BookSource::from_description`], also returning
+    /// the number of matching books the source reports in total.
+    /// Sources that report none return `None`.
+    fn from_description_with_total<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<IsbnsWithTotal, ReconError>> {
+        Box::pin(async move {
+            let isbns = self.from_description(client, config, description).await?;
+            Ok((isbns, None))
+        })
+    }
+
+    /// Performs a search restricted to books with matching `title` and `author`
+    /// returning ISBNs of matching books.
+    /// Sources without structured search fall back to a descriptive search of both.
+    fn from_title_author<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        title: &'a str,
+        author: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(async move {
+            let description = format!("{} {}", title, author);
+            self.from_description(client, config, &description).await
+        })
+    }
+}
+
+/// Stands in for a [`Source`] whose Cargo feature is disabled, failing every search
+#[derive(Debug)]
+struct Disabled(Source);
+
+impl Disabled {
+    fn error(&self) -> ReconError {
+        ReconError::Message(format!(
+            "Source {} is disabled, enable the `{}` feature of recon_metadata.",
+            self.0, self.0
+        ))
+    }
+}
+
+impl BookSource for Disabled {
+    fn from_isbn<'a>(
+        &'a self,
+        _client: &'a reqwest::Client,
+        _config: &'a ReconConfig,
+        _isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>> {
+        Box::pin(async move { Err(self.error()) })
+    }
+
+    fn from_description<'a>(
+        &'a self,
+        _client: &'a reqwest::Client,
+        _config: &'a ReconConfig,
+        _description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(async move { Err(self.error()) })
+    }
+}
+
+/// Maps a [`Source`] to its [`BookSource`] implementation
+pub(crate) fn book_source(source: &Source) -> Box<dyn BookSource> {
+    match source {
+        #[cfg(feature = "google_books")]
+        Source::GoogleBooks => Box::new(google_books::GoogleBooks::default()),
+        #[cfg(feature = "open_library")]
+        Source::OpenLibrary => Box::new(open_library::OpenLibrary::default()),
+        #[cfg(feature = "goodreads")]
+        Source::Goodreads => Box::new(goodreads::Goodreads),
+        #[cfg(feature = "amazon")]
+        Source::Amazon => Box::new(amazon::Amazon),
+        #[cfg(feature = "library_thing")]
+        Source::LibraryThing => Box::new(librarything::LibraryThing),
+        #[cfg(feature = "crossref")]
+        Source::Crossref => Box::new(crossref::Crossref),
+        #[cfg(feature = "mock")]
+        Source::Mock => Box::new(mock::MockSource),
+        #[allow(unreachable_patterns)]
+        source => Box::new(Disabled(*source)),
+    }
+}
+
+/// ISBN-13 and, unless it is `979` prefixed, ISBN-10 forms of `isbn`, canonical ISBN-13 first.
+/// Some sources index a book under only one of them.
+#[cfg_attr(
+    not(any(
+        feature = "google_books",
+        feature = "open_library",
+        feature = "crossref"
+    )),
+    allow(dead_code)
+)]
+pub(crate) fn isbn_forms(isbn: &Isbn) -> Vec<String> {
+    let isbn13 = match isbn {
+        Isbn::_10(isbn10) => Isbn13::from(*isbn10),
+        Isbn::_13(isbn13) => *isbn13,
+    };
+
+    let mut forms = vec![isbn13.to_string()];
+    forms.extend(Isbn10::try_from(isbn13).map(|isbn10| isbn10.to_string()));
+
+    forms
+}
+
+/// Parses a single pre-fetched record of `source` with its `Deserialize` impl,
+/// only sources with a JSON API have one.
+#[cfg_attr(
+    not(any(feature = "google_books", feature = "open_library")),
+    allow(unused_variables)
+)]
+pub(crate) fn from_value(
+    source: &Source,
+    value: &serde_json::Value,
+) -> Result<Metadata, ReconError> {
+    match source {
+        #[cfg(feature = "google_books")]
+        Source::GoogleBooks => google_books::GoogleBooks::from_value(value),
+        #[cfg(feature = "open_library")]
+        Source::OpenLibrary => open_library::OpenLibrary::from_value(value),
+        #[allow(unreachable_patterns)]
+        source => Err(ReconError::Message(format!(
+            "{} records cannot be parsed from JSON",
+            source
+        ))),
+    }
+}
+
+/// Delay requested by a `Retry-After` header, in seconds or until an HTTP date
+#[cfg_attr(
+    not(any(
+        feature = "google_books",
+        feature = "open_library",
+        feature = "goodreads",
+        feature = "amazon",
+        feature = "library_thing",
+        feature = "crossref"
+    )),
+    allow(dead_code)
+)]
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    parse_retry_after(
+        response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)?
+            .to_str()
+            .ok()?,
+        SystemTime::now(),
+    )
+}
+
+/// Parses a `Retry-After` value, `120` or `Wed, 21 Oct 2015 07:28:00 GMT`,
+/// into a delay from `now`. Dates in the past ask for no delay.
+#[cfg_attr(
+    not(any(
+        feature = "google_books",
+        feature = "open_library",
+        feature = "goodreads",
+        feature = "amazon",
+        feature = "library_thing",
+        feature = "crossref"
+    )),
+    allow(dead_code)
+)]
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+
+    match value.parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => httpdate::parse_http_date(value)
+            .ok()
+            .map(|date| date.duration_since(now).unwrap_or_default()),
+    }
+}
+
+/// Sends a GET request to `url`.
+///
+/// Connection errors, `429` and `5xx` responses are retried up to [`ReconConfig::max_attempts`]
+/// times with an exponential backoff starting from [`ReconConfig::retry_base_delay`].
+/// Each delay is randomly shortened by up to half to avoid retrying in lockstep,
+/// unless the response asks for a specific delay with a `Retry-After` header.
+///
+/// Rate limited requests that run out of attempts, or ask to wait longer than
+/// [`ReconConfig::max_retry_after`], fail with [`ReconError::RateLimited`].
+#[cfg_attr(
+    not(any(
+        feature = "google_books",
+        feature = "open_library",
+        feature = "goodreads",
+        feature = "amazon",
+        feature = "library_thing",
+        feature = "crossref"
+    )),
+    allow(dead_code)
+)]
+pub(crate) async fn fetch(
+    client: &reqwest::Client,
+    config: &ReconConfig,
+    url: &str,
+) -> Result<reqwest::Response, ReconError> {
+    let mut attempt = 1;
+
+    loop {
+        let response = client.get(url).send().await;
+
+        let (retryable, requested_delay) = match &response {
+            Ok(response) => (
+                response.status().is_server_error()
+                    || response.status() == StatusCode::TOO_MANY_REQUESTS,
+                retry_after(response),
+            ),
+            Err(err) => (err.is_connect(), None),
+        };
+
+        if let Some(delay) =
+            requested_delay.filter(|delay| retryable && *delay > config.max_retry_after)
+        {
+            debug!("{:#?} asks to retry in {:?}, giving up", url, delay);
+            return Err(ReconError::RateLimited {
+                retry_after: Some(delay),
+            });
+        }
+
+        if !retryable || attempt >= config.max_attempts {
+            if let Ok(response) = &response {
+                let status = response.status();
+
+                if status == StatusCode::TOO_MANY_REQUESTS
+                    || (status == StatusCode::SERVICE_UNAVAILABLE && requested_delay.is_some())
+                {
+                    return Err(ReconError::RateLimited {
+                        retry_after: requested_delay,
+                    });
+                }
+            }
+
+            return response
+                .and_then(|response| match response.status().is_server_error() {
+                    true => response.error_for_status(),
+                    false => Ok(response),
+                })
+                .map_err(ReconError::connection);
+        }
+
+        let delay = requested_delay.unwrap_or_else(|| {
+            let delay = config.retry_base_delay * 2u32.saturating_pow(attempt - 1);
+            delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+        });
+
+        debug!(
+            "Attempt {} for {:#?} failed, retrying in {:?}",
+            attempt, url, delay
+        );
+
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn init_logger() {
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    #[test]
+    fn derives_isbn_forms() {
+        use super::isbn_forms;
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        let forms = vec!["9781534431003".to_owned(), "1534431004".to_owned()];
+        assert_eq!(isbn_forms(&Isbn::from_str("1534431004").unwrap()), forms);
+        assert_eq!(isbn_forms(&Isbn::from_str("9781534431003").unwrap()), forms);
+        assert_eq!(
+            isbn_forms(&Isbn::from_str("9791032305690").unwrap()),
+            vec!["9791032305690".to_owned()]
+        );
+    }
+
+    /// Serves one response per connection with the given status codes, in order,
+    /// repeating the last one. `429` responses ask to be retried immediately.
+    /// Returns the server's URL and a request counter.
+    async fn serve(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+        serve_retry_after(statuses, "0").await
+    }
+
+    /// Like [`serve`], with `429` responses carrying the given `Retry-After` value
+    async fn serve_retry_after(
+        statuses: Vec<u16>,
+        retry_after: &'static str,
+    ) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let status = statuses[n.min(statuses.len() - 1)];
+                let mut buf = [0; 1024];
+                let _ = socket.read(&mut buf).await;
+                let retry_after = match status {
+                    429 => format!("retry-after: {}\r\n", retry_after),
+                    _ => String::new(),
+                };
+                let response = format!(
+                    "HTTP/1.1 {} Status\r\n{}content-length: 0\r\nconnection: close\r\n\r\n",
+                    status, retry_after
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors() {
+        use super::fetch;
+        use crate::recon::ReconConfig;
+        use std::time::Duration;
+
+        init_logger();
+
+        let (url, requests) = serve(vec![503, 503, 200]).await;
+        let config = ReconConfig {
+            retry_base_delay: Duration::from_millis(1),
+            ..ReconConfig::default()
+        };
+
+        let resp = fetch(&config.client().unwrap(), &config, &url).await;
+        assert!(resp.is_ok());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        use super::fetch;
+        use crate::recon::ReconConfig;
+
+        init_logger();
+
+        let (url, requests) = serve(vec![503]).await;
+        let config = ReconConfig {
+            max_attempts: 1,
+            ..ReconConfig::default()
+        };
+
+        let resp = fetch(&config.client().unwrap(), &config, &url).await;
+        assert!(resp.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn routes_through_proxy() {
+        use super::fetch;
+        use crate::recon::ReconConfig;
+
+        init_logger();
+
+        let (proxy, requests) = serve(vec![200]).await;
+        let config = ReconConfig::new().proxy(reqwest::Proxy::http(&proxy).unwrap());
+
+        // only reachable through the proxy, `.invalid` never resolves
+        let resp = fetch(&config.client().unwrap(), &config, "http://books.invalid/").await;
+        assert!(resp.is_ok());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn honors_retry_after() {
+        use super::fetch;
+        use crate::recon::{ReconConfig, ReconError};
+        use std::time::Duration;
+
+        init_logger();
+
+        let config = ReconConfig {
+            retry_base_delay: Duration::from_secs(60),
+            ..ReconConfig::default()
+        };
+
+        let (url, requests) = serve(vec![429, 200]).await;
+        let resp = fetch(&config.client().unwrap(), &config, &url).await;
+        assert!(resp.is_ok());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let config = ReconConfig {
+            max_attempts: 1,
+            ..config
+        };
+
+        let (url, _) = serve(vec![429]).await;
+        let resp = fetch(&config.client().unwrap(), &config, &url).await;
+        assert!(matches!(
+            resp,
+            Err(ReconError::RateLimited {
+                retry_after: Some(delay),
+            }) if delay == Duration::ZERO
+        ));
+    }
+
+    #[tokio::test]
+    async fn caps_retry_after() {
+        use super::fetch;
+        use crate::recon::{ReconConfig, ReconError};
+        use std::time::Duration;
+
+        init_logger();
+
+        let config = ReconConfig::new().max_retry_after(Duration::from_secs(60));
+
+        // a day is not waited for, and no further attempt is made
+        let (url, requests) = serve_retry_after(vec![429, 200], "86400").await;
+        let resp = fetch(&config.client().unwrap(), &config, &url).await;
+        assert!(matches!(
+            resp,
+            Err(ReconError::RateLimited {
+                retry_after: Some(delay),
+            }) if delay == Duration::from_secs(86400)
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        use super::parse_retry_after;
+        use std::time::{Duration, SystemTime};
+
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:30:00 GMT", now),
+            Some(Duration::from_secs(120))
+        );
+        // already past
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", SystemTime::now()), None);
+    }
+}
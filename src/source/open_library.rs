@@ -1,19 +1,55 @@
-use crate::metadata::Metadata;
-use crate::recon::ReconError;
+use crate::metadata::{CoverImage, Metadata};
+use crate::recon::{ReconConfig, ReconError, Source};
+use crate::source::{fetch, isbn_forms, BookSource, IsbnsWithTotal};
 use crate::util::translater;
+use futures::future::BoxFuture;
 use isbn2::Isbn;
 use log::debug;
 use serde::de;
 use serde::{Deserialize, Deserializer};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::marker::PhantomData;
 use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 /// A wrapper around [`Metadata`] for deserialization
 pub struct OpenLibrary(Metadata);
 
+/// Edition as returned by <https://openlibrary.org/isbn/{isbn}.json>
+#[derive(Debug, Deserialize)]
+struct Edition {
+    #[serde(default)]
+    works: Vec<WorkKey>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkKey {
+    key: String,
+}
+
+/// Work as returned by <https://openlibrary.org/works/{key}.json>
+#[derive(Debug, Deserialize)]
+struct Work {
+    description: Option<WorkDescription>,
+}
+
+/// Descriptions are either plain strings or typed text values
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum WorkDescription {
+    Text(String),
+    Typed { value: String },
+}
+
+impl From<WorkDescription> for String {
+    fn from(description: WorkDescription) -> Self {
+        match description {
+            WorkDescription::Text(value) | WorkDescription::Typed { value } => value,
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for OpenLibrary {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -22,12 +58,15 @@ impl<'de> Deserialize<'de> for OpenLibrary {
         enum Field {
             Identifiers,
             Title,
+            Subtitle,
             Authors,
             NumberOfPages,
             Publishers,
             PublishDate,
             Subjects,
             Cover,
+            Languages,
+            TableOfContents,
             Ignore,
         }
         struct FieldVisitor;
@@ -43,12 +82,15 @@ impl<'de> Deserialize<'de> for OpenLibrary {
                 match value {
                     "identifiers" => Ok(Field::Identifiers),
                     "title" => Ok(Field::Title),
+                    "subtitle" => Ok(Field::Subtitle),
                     "authors" => Ok(Field::Authors),
                     "number_of_pages" => Ok(Field::NumberOfPages),
                     "publishers" => Ok(Field::Publishers),
                     "publish_date" => Ok(Field::PublishDate),
                     "subjects" => Ok(Field::Subjects),
                     "cover" => Ok(Field::Cover),
+                    "languages" => Ok(Field::Languages),
+                    "table_of_contents" => Ok(Field::TableOfContents),
                     _ => Ok(Field::Ignore),
                 }
             }
@@ -79,12 +121,15 @@ impl<'de> Deserialize<'de> for OpenLibrary {
             {
                 let mut identifiers = None;
                 let mut title = None;
+                let mut subtitle = None;
                 let mut authors = None;
                 let mut number_of_pages = None;
                 let mut publishers = None;
                 let mut publish_date = None;
                 let mut subjects = None;
                 let mut cover = None;
+                let mut languages = None;
+                let mut table_of_contents = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -100,6 +145,12 @@ impl<'de> Deserialize<'de> for OpenLibrary {
                             }
                             title = Some(map.next_value()?);
                         }
+                        Field::Subtitle => {
+                            if subtitle.is_some() {
+                                return Err(de::Error::duplicate_field("subtitle"));
+                            }
+                            subtitle = Some(map.next_value()?);
+                        }
                         Field::Authors => {
                             if authors.is_some() {
                                 return Err(de::Error::duplicate_field("authors"));
@@ -136,6 +187,18 @@ impl<'de> Deserialize<'de> for OpenLibrary {
                             }
                             cover = Some(map.next_value()?);
                         }
+                        Field::Languages => {
+                            if languages.is_some() {
+                                return Err(de::Error::duplicate_field("languages"));
+                            }
+                            languages = Some(map.next_value()?);
+                        }
+                        Field::TableOfContents => {
+                            if table_of_contents.is_some() {
+                                return Err(de::Error::duplicate_field("table_of_contents"));
+                            }
+                            table_of_contents = Some(map.next_value()?);
+                        }
                         _ => {
                             let _ = match A::next_value::<de::IgnoredAny>(&mut map) {
                                 Ok(val) => val,
@@ -148,29 +211,42 @@ impl<'de> Deserialize<'de> for OpenLibrary {
                 }
 
                 Ok(OpenLibrary(Metadata {
-                    isbn10:           translater::openlibrary_isbn10(&identifiers),
-                    isbn13:           translater::openlibrary_isbn13(&identifiers),
-                    title:            translater::string(title),
-                    author:           translater::vec_hashmap_field(authors, "name"),
-                    description:      translater::empty(),
-                    page_count:       translater::number(number_of_pages),
-                    publisher:        translater::vec_hashmap_field(publishers, "name"),
-                    publication_date: translater::publication_date(publish_date),
-                    language:         translater::empty(),
-                    cover_image:      translater::openlibrary_cover_images(cover),
-                    tag:              translater::vec_hashmap_field_split(subjects, "name"),
+                    isbn10:            translater::openlibrary_isbn10(&identifiers),
+                    isbn13:            translater::openlibrary_isbn13(&identifiers),
+                    oclc:              translater::openlibrary_identifier(&identifiers, "oclc"),
+                    identifiers:       translater::openlibrary_identifiers(&identifiers),
+                    title:             translater::title(&title),
+                    subtitle:          translater::string(subtitle),
+                    series:            translater::series(&title),
+                    author:            translater::authors(translater::vec_hashmap_field(
+                        authors, "name",
+                    )),
+                    // fetched from the work, see `OpenLibrary::work_descriptions`
+                    description:       translater::empty(),
+                    page_count:        translater::number(number_of_pages),
+                    publisher:         translater::vec_hashmap_field(publishers, "name"),
+                    publication_date:  translater::publication_date(publish_date),
+                    language:          translater::openlibrary_languages(languages),
+                    cover_image:       translater::openlibrary_cover_images(cover),
+                    average_rating:    translater::empty(),
+                    ratings_count:     translater::empty(),
+                    tag:               translater::vec_hashmap_field_split(subjects, "name"),
+                    table_of_contents: translater::table_of_contents(table_of_contents),
                 }))
             }
         }
         const FIELDS: &[&str] = &[
             "identifiers",
             "title",
+            "subtitle",
             "authors",
             "number_of_pages",
             "publishers",
             "publish_date",
             "subjects",
             "cover",
+            "languages",
+            "table_of_contents",
         ];
         Deserializer::deserialize_struct(
             deserializer,
@@ -187,43 +263,234 @@ impl<'de> Deserialize<'de> for OpenLibrary {
 impl OpenLibrary {
     /// Performs an ISBN search using OpenLibrary API
     /// <https://openlibrary.org/developers/api>
-    pub async fn from_isbn(isbn: &isbn2::Isbn) -> Result<Metadata, ReconError> {
+    pub async fn from_isbn(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &isbn2::Isbn,
+    ) -> Result<Metadata, ReconError> {
+        Self::from_isbn_raw(client, config, isbn)
+            .await
+            .map(|(metadata, _)| metadata)
+    }
+
+    /// Same as [`OpenLibrary::from_isbn`] but the response body is returned
+    /// alongside the parsed [`Metadata`]
+    pub async fn from_isbn_raw(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &isbn2::Isbn,
+    ) -> Result<(Metadata, serde_json::Value), ReconError> {
+        // both ISBN forms, as editions are often only listed under one,
+        // the books returned for each are merged
+        let bibkeys = isbn_forms(isbn)
+            .iter()
+            .map(|form| format!("ISBN:{}", urlencoding::encode(form)))
+            .collect::<Vec<_>>()
+            .join(",");
         let req = format!(
-            "https://openlibrary.org/api/books?bibkeys=ISBN:{}&jscmd=data&format=json",
-            urlencoding::encode(&isbn.to_string())
+            "{}/api/books?bibkeys={}&jscmd=data&format=json",
+            config.api_base_url(&Source::OpenLibrary),
+            bibkeys
         );
 
         debug!("ISBN: {:#?}", &isbn);
         debug!("Request: {:#?}", &req);
 
-        let response = reqwest::get(req)
-            .await
-            .map_err(ReconError::Connection)?
-            .json::<HashMap<String, OpenLibrary>>()
+        let response = fetch(client, config, &req)
+            .await?
+            .json::<serde_json::Value>()
             .await
-            .map_err(ReconError::Connection)?;
+            .map_err(ReconError::connection)?;
 
         debug!("Response: {:#?}", &response);
 
-        let metadata = response.into_iter().map(|(_, v)| v.0).next();
+        let mut metadata =
+            translater::with_config(config, || Self::from_isbn_response(config, isbn, &response))?;
 
-        Ok(metadata.unwrap_or_default())
+        if config.open_library_descriptions {
+            // a missing description is not worth failing the whole lookup over
+            match Self::work_descriptions(client, config, isbn).await {
+                Ok(descriptions) => metadata.description.extend(descriptions),
+                Err(err) => debug!("Skipping work descriptions: {}", err),
+            }
+        }
+
+        Ok((metadata, response))
     }
 
-    /// Performs a descriptive search using OpenLibrary API
-    /// <https://openlibrary.org/developers/api>
-    pub async fn from_description(description: &str) -> Result<Vec<Isbn>, ReconError> {
+    /// Fetches descriptions of the works the edition with this ISBN belongs to
+    async fn work_descriptions(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        isbn: &Isbn,
+    ) -> Result<Vec<String>, ReconError> {
         let req = format!(
-            "https://openlibrary.org/search.json?q={}",
-            urlencoding::encode(description)
+            "{}/isbn/{}.json",
+            config.api_base_url(&Source::OpenLibrary),
+            urlencoding::encode(&isbn_forms(isbn)[0])
         );
 
+        debug!("Request: {:#?}", &req);
+
+        let edition = fetch(client, config, &req)
+            .await?
+            .json::<Edition>()
+            .await
+            .map_err(ReconError::connection)?;
+
+        let mut descriptions = Vec::new();
+
+        for work in edition.works {
+            // keys are paths such as `/works/OL20893680W`
+            let req = format!(
+                "{}{}.json",
+                config.api_base_url(&Source::OpenLibrary),
+                work.key
+            );
+
+            debug!("Request: {:#?}", &req);
+
+            let work = fetch(client, config, &req)
+                .await?
+                .json::<Work>()
+                .await
+                .map_err(ReconError::connection)?;
+
+            descriptions.extend(work.description.map(String::from));
+        }
+
+        Ok(descriptions)
+    }
+
+    /// Parses [`Metadata`] from a pre-fetched book object, the value of a bibkey such as
+    /// `ISBN:9781534431003` in an ISBN search response, without making a request
+    pub fn from_value(value: &serde_json::Value) -> Result<Metadata, ReconError> {
+        OpenLibrary::deserialize(value)
+            .map(|book| book.0)
+            .map_err(ReconError::JSONParse)
+    }
+
+    /// Parses [`Metadata`] from the response body of an ISBN search
+    fn from_isbn_response(
+        config: &ReconConfig,
+        isbn: &Isbn,
+        response: &serde_json::Value,
+    ) -> Result<Metadata, ReconError> {
+        let books =
+            HashMap::<String, OpenLibrary>::deserialize(response).map_err(ReconError::JSONParse)?;
+
+        // an empty object if there is no such book,
+        // every book returned is merged as `HashMap` iteration order is arbitrary
+        let mut metadata = books
+            .into_values()
+            .map(|v| v.0)
+            .reduce(|merged, m| merged + m)
+            .ok_or_else(|| ReconError::NotFound(isbn.clone()))?;
+
+        if metadata.cover_image.is_empty() {
+            metadata.cover_image = Self::cover_from_isbn(config, isbn);
+        }
+
+        Ok(metadata)
+    }
+
+    /// Cover image URLs of the OpenLibrary Covers API, which often has a cover
+    /// for books whose data lacks one. Missing covers respond with `404`.
+    /// <https://openlibrary.org/dev/docs/api/covers>
+    ///
+    /// URLs are built under [`ReconConfig::open_library_covers_url`].
+    pub fn cover_from_isbn(config: &ReconConfig, isbn: &Isbn) -> CoverImage {
+        let url = |size: &str| {
+            let mut urls = HashSet::new();
+            urls.insert(format!(
+                "{}/b/isbn/{}-{}.jpg?default=false",
+                config.open_library_covers_url.trim_end_matches('/'),
+                urlencoding::encode(&isbn.to_string()),
+                size
+            ));
+            urls
+        };
+
+        CoverImage {
+            small: url("S"),
+            medium: url("M"),
+            large: url("L"),
+            ..CoverImage::default()
+        }
+    }
+
+    /// Performs a descriptive search using OpenLibrary API
+    /// <https://openlibrary.org/developers/api>
+    pub async fn from_description(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        description: &str,
+    ) -> Result<Vec<Isbn>, ReconError> {
+        Self::from_description_with_total(client, config, description)
+            .await
+            .map(|(isbns, _)| isbns)
+    }
+
+    /// Same as [`OpenLibrary::from_description`], also returning the `numFound` reported
+    pub async fn from_description_with_total(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        description: &str,
+    ) -> Result<IsbnsWithTotal, ReconError> {
         debug!("Description: {:#?}", &description);
+
+        let params = format!("q={}", urlencoding::encode(description));
+
+        Self::search(client, config, &params).await
+    }
+
+    /// Performs a search restricted to the `title` and `author` fields of OpenLibrary API
+    /// <https://openlibrary.org/dev/docs/api/search>
+    pub async fn from_title_author(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        title: &str,
+        author: &str,
+    ) -> Result<Vec<Isbn>, ReconError> {
+        debug!("Title: {:#?}, Author: {:#?}", &title, &author);
+
+        let params = format!(
+            "title={}&author={}",
+            urlencoding::encode(title),
+            urlencoding::encode(author)
+        );
+
+        Self::search(client, config, &params)
+            .await
+            .map(|(isbns, _)| isbns)
+    }
+
+    /// Returns an ISBN from each document matching the URL encoded query `params`
+    /// and the total number of matching documents
+    async fn search(
+        client: &reqwest::Client,
+        config: &ReconConfig,
+        params: &str,
+    ) -> Result<IsbnsWithTotal, ReconError> {
+        let mut req = format!(
+            "{}/search.json?{}&offset={}",
+            config.api_base_url(&Source::OpenLibrary),
+            params,
+            config.description_offset
+        );
+
+        // prefers editions and works in this language
+        if let Some(language) = &config.preferred_language {
+            req.push_str(&format!("&lang={}", urlencoding::encode(language)));
+        }
+
         debug!("Request: {:#?}", &req);
 
         #[derive(Deserialize, Debug)]
         struct Docs {
-            docs: Vec<OLIsbn>,
+            #[serde(rename = "numFound")]
+            num_found: Option<usize>,
+            docs:      Vec<OLIsbn>,
         }
 
         #[derive(Deserialize, Debug)]
@@ -231,24 +498,21 @@ impl OpenLibrary {
             isbn: Option<Vec<String>>,
         }
 
-        let response = reqwest::get(req)
-            .await
-            .map_err(ReconError::Connection)?
+        let response = fetch(client, config, &req)
+            .await?
             .json::<Docs>()
             .await
-            .map_err(ReconError::Connection)?;
+            .map_err(ReconError::connection)?;
 
         debug!("Response: {:#?}", &response);
 
         let mut isbns = response
             .docs
             .iter()
-            .map(|h| h.isbn.as_ref().map(|v| v.get(0)))
-            .flatten()
-            .flatten()
+            .filter_map(|h| h.isbn.as_ref().and_then(|v| v.first()))
             .collect::<Vec<_>>();
 
-        isbns.truncate(3); // first 3 results
+        isbns.truncate(config.description_limit);
 
         let mut isbn_list = Vec::new();
 
@@ -256,13 +520,52 @@ impl OpenLibrary {
             isbn_list.push(Isbn::from_str(isbn));
         }
 
-        let isbn_list = isbn_list
-            .into_iter()
-            .filter(|isbn| isbn.is_ok())
-            .map(|isbn| isbn.unwrap())
-            .collect::<Vec<_>>();
+        let isbn_list = isbn_list.into_iter().flatten().collect::<Vec<_>>();
+
+        Ok((isbn_list, response.num_found))
+    }
+}
+
+impl BookSource for OpenLibrary {
+    fn from_isbn<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        isbn: &'a Isbn,
+    ) -> BoxFuture<'a, Result<Metadata, ReconError>> {
+        Box::pin(Self::from_isbn(client, config, isbn))
+    }
+
+    fn from_description<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(Self::from_description(client, config, description))
+    }
 
-        Ok(isbn_list)
+    fn from_description_with_total<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        description: &'a str,
+    ) -> BoxFuture<'a, Result<IsbnsWithTotal, ReconError>> {
+        Box::pin(Self::from_description_with_total(
+            client,
+            config,
+            description,
+        ))
+    }
+
+    fn from_title_author<'a>(
+        &'a self,
+        client: &'a reqwest::Client,
+        config: &'a ReconConfig,
+        title: &'a str,
+        author: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Isbn>, ReconError>> {
+        Box::pin(Self::from_title_author(client, config, title, author))
     }
 }
 
@@ -276,6 +579,7 @@ mod test {
     #[tokio::test]
     async fn parses_from_isbn() {
         use super::OpenLibrary;
+        use crate::recon::ReconConfig;
         use isbn2::Isbn;
         use log::debug;
         use std::str::FromStr;
@@ -283,20 +587,130 @@ mod test {
         init_logger();
 
         let isbn = Isbn::from_str("9781534431003").unwrap();
-        let resp = OpenLibrary::from_isbn(&isbn).await;
+        let config = ReconConfig::default();
+        let resp = OpenLibrary::from_isbn(&config.client().unwrap(), &config, &isbn).await;
         debug!("Response: {:#?}", resp);
         assert!(resp.is_ok())
     }
 
+    #[test]
+    fn parses_from_isbn_response() {
+        use super::OpenLibrary;
+        use crate::recon::ReconConfig;
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        let response = serde_json::json!({
+            "ISBN:9781534431003": {
+                "title": "This Is How You Lose the Time War",
+                "authors": [{ "name": "Amal El-Mohtar" }, { "name": "Max Gladstone" }],
+                "identifiers": {
+                    "isbn_13": ["9781534431003"],
+                    "oclc": ["1100424998"],
+                    "goodreads": ["43352954"]
+                },
+                "languages": [{ "key": "/languages/eng" }]
+            }
+        });
+
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let metadata =
+            OpenLibrary::from_isbn_response(&ReconConfig::default(), &isbn, &response).unwrap();
+        assert!(metadata.title.contains("This Is How You Lose the Time War"));
+        assert_eq!(metadata.author.len(), 2);
+        assert_eq!(metadata.isbn13.len(), 1);
+        assert!(metadata.oclc.contains("1100424998"));
+        assert!(metadata.identifiers["goodreads"].contains("43352954"));
+        assert!(!metadata.identifiers.contains_key("isbn_13"));
+        assert!(!metadata.identifiers.contains_key("oclc"));
+        assert!(metadata.language.contains("en"));
+        assert!(metadata
+            .cover_image
+            .large
+            .contains("https://covers.openlibrary.org/b/isbn/9781534431003-L.jpg?default=false"));
+
+        let mirror = ReconConfig::new().open_library_covers_url("http://covers.mirror.test/");
+        let metadata = OpenLibrary::from_isbn_response(&mirror, &isbn, &response).unwrap();
+        assert!(metadata
+            .cover_image
+            .large
+            .contains("http://covers.mirror.test/b/isbn/9781534431003-L.jpg?default=false"));
+    }
+
+    #[test]
+    fn parses_from_value() {
+        use super::OpenLibrary;
+
+        let book = serde_json::json!({
+            "title": "This Is How You Lose the Time War",
+            "publishers": [{ "name": "Saga Press" }],
+            "table_of_contents": [
+                { "level": 0, "title": "One", "pagenum": "1" },
+                { "level": 0, "title": "Two", "pagenum": "9" }
+            ]
+        });
+
+        let metadata = OpenLibrary::from_value(&book).unwrap();
+        assert!(metadata.title.contains("This Is How You Lose the Time War"));
+        assert!(metadata.publisher.contains("Saga Press"));
+        assert_eq!(metadata.table_of_contents(), ["One", "Two"]);
+    }
+
+    #[test]
+    fn parses_work_description() {
+        use super::Work;
+
+        let work: Work = serde_json::from_value(serde_json::json!({
+            "description": "Two time-traveling agents."
+        }))
+        .unwrap();
+        assert_eq!(
+            work.description.map(String::from).as_deref(),
+            Some("Two time-traveling agents.")
+        );
+
+        let work: Work = serde_json::from_value(serde_json::json!({
+            "description": { "type": "/type/text", "value": "Two time-traveling agents." }
+        }))
+        .unwrap();
+        assert_eq!(
+            work.description.map(String::from).as_deref(),
+            Some("Two time-traveling agents.")
+        );
+
+        let work: Work = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(work.description.is_none());
+    }
+
+    #[test]
+    fn reports_isbn_not_found() {
+        use super::OpenLibrary;
+        use crate::recon::ReconConfig;
+        use crate::recon::ReconError;
+        use isbn2::Isbn;
+        use std::str::FromStr;
+
+        let isbn = Isbn::from_str("9781534431003").unwrap();
+        let response = serde_json::json!({});
+
+        assert!(matches!(
+            OpenLibrary::from_isbn_response(&ReconConfig::default(), &isbn, &response),
+            Err(ReconError::NotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn parses_from_description() {
         use super::OpenLibrary;
+        use crate::recon::ReconConfig;
         use log::debug;
 
         init_logger();
 
         let description = "This is how you lose the time war";
-        let resp = OpenLibrary::from_description(description).await;
+        let config = ReconConfig::default();
+        let resp =
+            OpenLibrary::from_description(&config.client().unwrap(), &config, description).await;
         debug!("Response: {:#?}", resp);
         assert!(resp.is_ok())
     }
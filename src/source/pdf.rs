@@ -0,0 +1,348 @@
+use crate::metadata::Metadata;
+use crate::recon::ReconError;
+use crate::util::translater;
+use isbn2::Isbn;
+use log::debug;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Dublin Core elements namespace used by XMP
+const DC: &str = "http://purl.org/dc/elements/1.1/";
+/// Adobe PDF namespace of XMP, holding `pdf:Keywords`
+const XMP_PDF: &str = "http://ns.adobe.com/pdf/1.3/";
+
+/// Reads the [`Metadata`] of the PDF at `path`
+pub(crate) fn from_path(path: &Path) -> Result<Metadata, ReconError> {
+    let pdf = fs::read(path).map_err(ReconError::Io)?;
+
+    Ok(from_bytes(&pdf))
+}
+
+/// Maps the document information dictionary and XMP packets of `pdf` into [`Metadata`],
+/// empty if it has neither.
+///
+/// Only objects outside of compressed object streams are read, XMP packets are left
+/// uncompressed by most writers so that they can be found this way.
+fn from_bytes(pdf: &[u8]) -> Metadata {
+    let mut metadata = Metadata::default();
+    let mut texts = Vec::new();
+
+    if let Some(info) = info_dictionary(pdf) {
+        let title = string_after(info, "/Title");
+        metadata.title.extend(translater::title(&title));
+
+        let author = string_after(info, "/Author");
+        metadata.author.extend(translater::authors(author.iter()));
+
+        let keywords_value = string_after(info, "/Keywords");
+        metadata
+            .tag
+            .extend(keywords_value.iter().flat_map(|value| keywords(value)));
+
+        // free text, only looked through for an ISBN
+        let subject = string_after(info, "/Subject");
+
+        // `[..].into_iter()` would iterate by reference in edition 2018
+        texts.extend(IntoIterator::into_iter([title, author, keywords_value, subject]).flatten());
+    }
+
+    for packet in xmp_packets(pdf) {
+        match roxmltree::Document::parse(&packet) {
+            Ok(document) => texts.extend(from_xmp(&document, &mut metadata)),
+            Err(err) => debug!("Skipping malformed XMP packet: {}", err),
+        }
+    }
+
+    for isbn in texts.iter().flat_map(|text| isbns(text)) {
+        match isbn {
+            Isbn::_10(isbn10) => {
+                metadata.isbn10.insert(isbn10);
+            }
+            Isbn::_13(isbn13) => {
+                metadata.isbn13.insert(isbn13);
+            }
+        }
+    }
+
+    metadata.normalize_isbns();
+
+    metadata
+}
+
+/// Keywords separated by `;` or `,`, as tags
+fn keywords(s: &str) -> Vec<String> {
+    s.split(';').flat_map(translater::tags).collect()
+}
+
+/// Valid ISBNs written in `text`, with or without hyphens
+fn isbns(text: &str) -> Vec<Isbn> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '-' || c == 'X' || c == 'x'))
+        .map(|token| token.replace('-', ""))
+        .filter(|digits| digits.len() == 10 || digits.len() == 13)
+        .filter_map(|digits| Isbn::from_str(&digits).ok())
+        .collect()
+}
+
+/// Maps the Dublin Core, `pdf:Keywords` and `prism:isbn` properties of an XMP packet,
+/// returning every value to look for ISBNs in.
+fn from_xmp(document: &roxmltree::Document, metadata: &mut Metadata) -> Vec<String> {
+    let mut texts = Vec::new();
+
+    for node in document.descendants().filter(|node| node.is_element()) {
+        // `pdf:Keywords` and `prism:isbn` may also be attributes of `rdf:Description`
+        for attribute in node.attributes() {
+            match (attribute.namespace(), attribute.name()) {
+                (Some(XMP_PDF), "Keywords") => metadata.tag.extend(keywords(attribute.value())),
+                (Some(namespace), "isbn") if namespace.contains("prismstandard.org") => {}
+                _ => continue,
+            }
+            texts.push(attribute.value().to_owned());
+        }
+
+        // array properties list their values in `rdf:li`, simple ones hold text
+        let values = match node.descendants().any(|child| child.has_tag_name("li")) {
+            true => node
+                .descendants()
+                .filter(|child| child.has_tag_name("li"))
+                .filter_map(|child| child.text())
+                .collect::<Vec<_>>(),
+            false => node.text().into_iter().collect(),
+        };
+        let values = values
+            .into_iter()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<_>>();
+
+        match (node.tag_name().namespace(), node.tag_name().name()) {
+            (Some(DC), "title") => {
+                for value in &values {
+                    metadata
+                        .title
+                        .extend(translater::title(&Some((*value).to_owned())));
+                }
+            }
+            (Some(DC), "creator") => metadata.author.extend(translater::authors(&values)),
+            (Some(DC), "subject") => metadata.tag.extend(values.iter().flat_map(|v| keywords(v))),
+            (Some(DC), "identifier") => {}
+            (Some(XMP_PDF), "Keywords") => {
+                metadata.tag.extend(values.iter().flat_map(|v| keywords(v)))
+            }
+            (Some(namespace), "isbn") if namespace.contains("prismstandard.org") => {}
+            _ => continue,
+        }
+
+        texts.extend(values.into_iter().map(str::to_owned));
+    }
+
+    texts
+}
+
+/// Every `<x:xmpmeta>` element in `pdf`
+fn xmp_packets(pdf: &[u8]) -> Vec<String> {
+    const START: &[u8] = b"<x:xmpmeta";
+    const END: &[u8] = b"</x:xmpmeta>";
+
+    let mut packets = Vec::new();
+    let mut rest = pdf;
+
+    while let Some(start) = find(rest, START) {
+        let end = match find(&rest[start..], END) {
+            Some(end) => start + end + END.len(),
+            None => break,
+        };
+
+        packets.push(String::from_utf8_lossy(&rest[start..end]).into_owned());
+        rest = &rest[end..];
+    }
+
+    packets
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Body of the object referenced by the last `/Info N G R` of the trailers,
+/// up to its `endobj`
+fn info_dictionary(pdf: &[u8]) -> Option<&[u8]> {
+    // incremental updates append trailers, the last one is current
+    let at = pdf.windows(5).rposition(|window| window == b"/Info")?;
+    let reference = String::from_utf8_lossy(&pdf[at + 5..(at + 40).min(pdf.len())]).into_owned();
+    let mut parts = reference.split_whitespace();
+    let (number, generation) = (parts.next()?, parts.next()?);
+    if !parts.next()?.starts_with('R') {
+        return None;
+    }
+
+    let header = format!("{} {} obj", number, generation);
+    let start = pdf
+        .windows(header.len())
+        .enumerate()
+        .filter(|(_, window)| *window == header.as_bytes())
+        // `11 0 obj` also ends with `1 0 obj`
+        .filter(|(at, _)| *at == 0 || !pdf[at - 1].is_ascii_digit())
+        .map(|(at, _)| at + header.len())
+        .next_back()?;
+    let end = start + find(&pdf[start..], b"endobj")?;
+
+    Some(&pdf[start..end])
+}
+
+/// String value following `key` in a dictionary, literal `(...)` or hexadecimal `<...>`
+fn string_after(dictionary: &[u8], key: &str) -> Option<String> {
+    let at = find(dictionary, key.as_bytes())? + key.len();
+    let value = dictionary[at..].trim_ascii_start();
+
+    let bytes = match value.first()? {
+        b'(' => literal_string(&value[1..])?,
+        b'<' if value.get(1) != Some(&b'<') => hex_string(&value[1..])?,
+        _ => return None,
+    };
+
+    let text = decode_text(&bytes);
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_owned())
+}
+
+/// Bytes of a literal string up to its closing parenthesis, escapes resolved
+fn literal_string(s: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut depth = 0;
+    let mut chars = s.iter().copied().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            b'\\' => match chars.next()? {
+                b'n' => bytes.push(b'\n'),
+                b'r' => bytes.push(b'\r'),
+                b't' => bytes.push(b'\t'),
+                b'b' => bytes.push(0x08),
+                b'f' => bytes.push(0x0c),
+                b'\r' | b'\n' => {} // line continuation
+                digit @ b'0'..=b'7' => {
+                    let mut code = (digit - b'0') as u32;
+                    for _ in 0..2 {
+                        match chars.peek() {
+                            Some(next @ b'0'..=b'7') => {
+                                code = code * 8 + (next - b'0') as u32;
+                                chars.next();
+                            }
+                            _ => break,
+                        }
+                    }
+                    bytes.push(code as u8);
+                }
+                other => bytes.push(other),
+            },
+            b'(' => {
+                depth += 1;
+                bytes.push(c);
+            }
+            b')' if depth == 0 => return Some(bytes),
+            b')' => {
+                depth -= 1;
+                bytes.push(c);
+            }
+            _ => bytes.push(c),
+        }
+    }
+
+    None
+}
+
+/// Bytes of a hexadecimal string up to its closing `>`
+fn hex_string(s: &[u8]) -> Option<Vec<u8>> {
+    let end = s.iter().position(|&c| c == b'>')?;
+    let mut digits = s[..end]
+        .iter()
+        .filter(|c| !c.is_ascii_whitespace())
+        .map(|&c| (c as char).to_digit(16))
+        .collect::<Option<Vec<_>>>()?;
+
+    // a missing final digit is taken as 0
+    if digits.len() % 2 == 1 {
+        digits.push(0);
+    }
+
+    Some(
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0] * 16 + pair[1]) as u8)
+            .collect(),
+    )
+}
+
+/// Decodes a text string, UTF-16BE when it starts with a byte order mark
+/// and otherwise PDFDocEncoding, taken as Latin-1
+fn decode_text(bytes: &[u8]) -> String {
+    match bytes {
+        [0xfe, 0xff, utf16 @ ..] => {
+            let units = utf16
+                .chunks(2)
+                .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]))
+                .collect::<Vec<_>>();
+            String::from_utf16_lossy(&units)
+        }
+        _ => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_bytes, literal_string};
+
+    #[test]
+    fn reads_info_dictionary_and_xmp() {
+        let pdf = br#"%PDF-1.4
+1 0 obj
+<< /Type /Catalog /Pages 2 0 R /Metadata 4 0 R >>
+endobj
+3 0 obj
+<< /Title (Dry \(Advance Copy\)) /Author (Neal Shusterman; Jarrod Shusterman)
+   /Subject <FEFF004900530042004E0020003900370038002D0031002D0035003300340034002D0033003100300030002D0033>
+   /Keywords (Young Adult Fiction; Dystopian) >>
+endobj
+4 0 obj
+<< /Type /Metadata /Subtype /XML >>
+stream
+<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:pdf="http://ns.adobe.com/pdf/1.3/" pdf:Keywords="Climate Fiction">
+      <dc:title><rdf:Alt><rdf:li xml:lang="x-default">Dry</rdf:li></rdf:Alt></dc:title>
+      <dc:creator><rdf:Seq><rdf:li>Neal Shusterman</rdf:li></rdf:Seq></dc:creator>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>
+endstream
+endobj
+trailer
+<< /Root 1 0 R /Info 3 0 R >>
+%%EOF"#;
+
+        let metadata = from_bytes(pdf);
+        assert!(metadata.title().contains("Dry (Advance Copy)"));
+        assert!(metadata.title().contains("Dry"));
+        assert_eq!(metadata.author().len(), 2);
+        assert!(metadata.tag().contains("young-adult-fiction"));
+        assert!(metadata.tag().contains("climate-fiction"));
+        assert_eq!(metadata.isbn13().len(), 1);
+
+        assert!(from_bytes(b"%PDF-1.4\n%%EOF").is_empty());
+    }
+
+    #[test]
+    fn unescapes_literal_strings() {
+        assert_eq!(
+            literal_string(br"a (b) \051\\\n) rest").unwrap(),
+            b"a (b) )\\\n".to_vec()
+        );
+        assert!(literal_string(b"unterminated").is_none());
+    }
+}
@@ -0,0 +1,260 @@
+//! Decompresses raw DEFLATE streams, as found in zip archives.
+//! <https://www.rfc-editor.org/rfc/rfc1951>
+
+/// Base lengths of the length symbols `257..=285`
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+/// Extra bits read after each length symbol
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distances of the distance symbols `0..=29`
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+/// Extra bits read after each distance symbol
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order in which the code length code lengths of a dynamic block are sent
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Reads bits least significant first, as DEFLATE packs them
+struct Bits<'a> {
+    data:   &'a [u8],
+    at:     usize,
+    buffer: u32,
+    count:  u32,
+}
+
+impl Bits<'_> {
+    fn read(&mut self, n: u32) -> Option<u32> {
+        while self.count < n {
+            self.buffer |= (*self.data.get(self.at)? as u32) << self.count;
+            self.at += 1;
+            self.count += 8;
+        }
+
+        let bits = self.buffer & ((1 << n) - 1);
+        self.buffer >>= n;
+        self.count -= n;
+
+        Some(bits)
+    }
+
+    /// Drops the bits left of the current byte, stored blocks start on a byte boundary
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+/// A canonical Huffman code, as the number of codes of each length
+/// and the symbols ordered by code
+struct Huffman {
+    counts:  [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Builds the code from the code length of each symbol, `0` for unused symbols
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate().filter(|(_, &l)| l != 0) {
+            symbols[offsets[length as usize] as usize] = symbol as u16;
+            offsets[length as usize] += 1;
+        }
+
+        Huffman { counts, symbols }
+    }
+
+    /// Reads the next symbol, codes are packed most significant bit first
+    fn decode(&self, bits: &mut Bits) -> Option<u16> {
+        let (mut code, mut first, mut index) = (0, 0, 0);
+
+        for &count in &self.counts[1..] {
+            code |= bits.read(1)? as usize;
+            let count = count as usize;
+            if code < first + count {
+                return self.symbols.get(index + code - first).copied();
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        None
+    }
+}
+
+/// Codes of blocks compressed with the fixed Huffman codes
+fn fixed() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+/// Codes sent at the start of a block compressed with dynamic Huffman codes
+fn dynamic(bits: &mut Bits) -> Option<(Huffman, Huffman)> {
+    let literals = bits.read(5)? as usize + 257;
+    let distances = bits.read(5)? as usize + 1;
+    let code_lengths = bits.read(4)? as usize + 4;
+
+    let mut lengths = [0; 19];
+    for &symbol in &CODE_LENGTH_ORDER[..code_lengths] {
+        lengths[symbol] = bits.read(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&lengths);
+
+    // literal/length and distance code lengths are sent as one sequence
+    let mut lengths = Vec::with_capacity(literals + distances);
+    while lengths.len() < literals + distances {
+        let (length, repeat) = match code_length_code.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last()?, 3 + bits.read(2)?),
+            17 => (0, 3 + bits.read(3)?),
+            18 => (0, 11 + bits.read(7)?),
+            _ => return None,
+        };
+        lengths.extend(std::iter::repeat_n(length, repeat as usize));
+    }
+    if lengths.len() > literals + distances {
+        return None;
+    }
+
+    Some((
+        Huffman::new(&lengths[..literals]),
+        Huffman::new(&lengths[literals..]),
+    ))
+}
+
+/// Decodes a block compressed with `literal` and `distance` codes onto `out`,
+/// failing once it holds more than `size` bytes
+fn codes(
+    bits: &mut Bits,
+    out: &mut Vec<u8>,
+    size: usize,
+    literal: &Huffman,
+    distance: &Huffman,
+) -> Option<()> {
+    while out.len() <= size {
+        match literal.decode(bits)? {
+            symbol @ 0..=255 => out.push(symbol as u8),
+            256 => return Some(()),
+            symbol => {
+                let symbol = symbol as usize - 257;
+                let length = *LENGTH_BASE.get(symbol)? as usize
+                    + bits.read(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+                let symbol = distance.decode(bits)? as usize;
+                let distance = *DISTANCE_BASE.get(symbol)? as usize
+                    + bits.read(DISTANCE_EXTRA[symbol] as u32)? as usize;
+
+                // copies may overlap the bytes they produce, e.g. a run of one byte
+                let start = out.len().checked_sub(distance)?;
+                for at in start..start + length {
+                    out.push(out[at]);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Decompresses the raw DEFLATE stream `data` into `size` bytes,
+/// `None` if it is malformed or does not hold exactly `size` bytes.
+pub(crate) fn inflate(data: &[u8], size: usize) -> Option<Vec<u8>> {
+    let mut bits = Bits {
+        data,
+        at: 0,
+        buffer: 0,
+        count: 0,
+    };
+    // `size` comes from the archive, it is not trusted to reserve memory up front
+    let mut out = Vec::with_capacity(size.min(data.len()));
+
+    loop {
+        let last = bits.read(1)? == 1;
+
+        match bits.read(2)? {
+            0 => {
+                bits.align();
+                let header = data.get(bits.at..bits.at + 4)?;
+                let length = u16::from_le_bytes([header[0], header[1]]);
+                if length != !u16::from_le_bytes([header[2], header[3]]) {
+                    return None;
+                }
+                let start = bits.at + 4;
+                out.extend_from_slice(data.get(start..start + length as usize)?);
+                bits.at = start + length as usize;
+            }
+            1 => {
+                let (literal, distance) = fixed();
+                codes(&mut bits, &mut out, size, &literal, &distance)?;
+            }
+            2 => {
+                let (literal, distance) = dynamic(&mut bits)?;
+                codes(&mut bits, &mut out, size, &literal, &distance)?;
+            }
+            _ => return None,
+        }
+
+        // the size of the entry bounds the output of a corrupted or malicious stream
+        if out.len() > size {
+            return None;
+        }
+        if last {
+            break;
+        }
+    }
+
+    (out.len() == size).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inflate;
+
+    #[test]
+    fn inflates_blocks() {
+        let text = b"Dry, Dry, Dry by Neal Shusterman and Jarrod Shusterman";
+
+        // raw DEFLATE of `text` by zlib, as stored and with fixed codes
+        let stored = [&[0x01, 0x36, 0x00, 0xc9, 0xff][..], &text[..]].concat();
+        assert_eq!(inflate(&stored, text.len()).unwrap(), text);
+
+        let fixed = [
+            0x73, 0x29, 0xaa, 0xd4, 0x51, 0x70, 0x81, 0x12, 0x0a, 0x49, 0x95, 0x0a, 0x7e, 0xa9,
+            0x89, 0x39, 0x0a, 0xc1, 0x19, 0xa5, 0xc5, 0x25, 0xa9, 0x45, 0xb9, 0x89, 0x79, 0x0a,
+            0x89, 0x79, 0x29, 0x0a, 0x5e, 0x89, 0x45, 0x45, 0xf9, 0x29, 0x48, 0xa2, 0x00,
+        ];
+        assert_eq!(inflate(&fixed, text.len()).unwrap(), text);
+
+        // a stream holding more or fewer bytes than expected
+        assert!(inflate(&fixed, text.len() - 1).is_none());
+        assert!(inflate(&fixed, text.len() + 1).is_none());
+        assert!(inflate(&fixed[..10], text.len()).is_none());
+    }
+}
@@ -1,2 +1,5 @@
+/// Decompression of DEFLATE streams, for zipped EPUBs
+#[cfg(feature = "epub")]
+pub(crate) mod inflate;
 /// A set of helper functions to parse API responses
 pub(crate) mod translater;
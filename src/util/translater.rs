@@ -3,14 +3,37 @@
 /// Different book API responses are usually similar in shape so this module's job
 /// is to provide multipurpose functions that can be applied to a piece of `JSON` data
 /// provided by `serde` via `Source` module and translate them into `Metadata` type
-use crate::metadata::CoverImage;
-use chrono::NaiveDate;
+use crate::metadata::{CoverImage, PubDate};
+use crate::recon::ReconConfig;
+use chrono::{Datelike, NaiveDate};
 use isbn2::{Isbn10, Isbn13};
 use std::{
+    cell::{Cell, RefCell},
     collections::{HashMap, HashSet},
     str::FromStr,
 };
 
+thread_local! {
+    /// [`ReconConfig::date_formats`] for the response being translated, see [`with_config`]
+    static DATE_FORMATS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    /// [`ReconConfig::keep_last_first`] for the response being translated, see [`with_config`]
+    static KEEP_LAST_FIRST: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `translate` with the date formats and author name order of `config`.
+///
+/// Responses are translated by `Deserialize` impls which cannot be handed a configuration,
+/// since translating is synchronous the settings are kept for the current thread only.
+pub(crate) fn with_config<T>(config: &ReconConfig, translate: impl FnOnce() -> T) -> T {
+    let previous_formats = DATE_FORMATS.with(|f| f.replace(config.date_formats.clone()));
+    let previous_order = KEEP_LAST_FIRST.with(|k| k.replace(config.keep_last_first));
+    let translated = translate();
+    DATE_FORMATS.with(|f| f.replace(previous_formats));
+    KEEP_LAST_FIRST.with(|k| k.set(previous_order));
+
+    translated
+}
+
 /// Helper function that takes an [`Option`] value and converts it into an [`HashSet`]
 /// by mapping [`None`] to empty [`HashSet`] and [`Some`] to an inserted element.
 /// `Metadata` struct contains a [`HashSet`] for each of its fields
@@ -34,18 +57,15 @@ pub(crate) fn optional_to_hashset<T: std::hash::Hash + std::cmp::Eq>(
 pub(crate) fn hashset_fallback<T: std::hash::Hash + std::cmp::Eq>(
     value: Option<HashSet<T>>,
 ) -> HashSet<T> {
-    match value {
-        Some(value) => value,
-        None => HashSet::new(),
-    }
+    value.unwrap_or_default()
 }
 
-pub(crate) fn empty() -> HashSet<String> {
+pub(crate) fn empty<T>() -> HashSet<T> {
     HashSet::new()
 }
 
-/// Following functions translate `serde` values into values compatible with `Metadata` field.
-/// "..." means 'doesn't matter' or handled by `serde` `deserialize` implementation elsewhere.
+// Following functions translate `serde` values into values compatible with `Metadata` field.
+// "..." means 'doesn't matter' or handled by `serde` `deserialize` implementation elsewhere.
 
 /// Example use-case:
 /// { "...": 42 } -> Serde { 42 } -> [42]
@@ -60,11 +80,274 @@ pub(crate) fn string(s: Option<String>) -> HashSet<String> {
 }
 
 /// Example use-case:
-/// { "...": ["some string", "some other string", "some string"] }
-///   -> Serde { ["some string", "some other string", "some string"] }
-///   -> ["some string", "some other string"]
-pub(crate) fn vec(vec: Option<Vec<&str>>) -> HashSet<String> {
-    hashset_fallback(vec.map(|vec| vec.into_iter().map(|v| v.to_owned()).collect()))
+/// { "...": "<p>First <i>paragraph</i></p><p>Second<br>paragraph</p>" }
+///   -> Serde { "<p>First <i>paragraph</i></p><p>Second<br>paragraph</p>" }
+///   -> ["First paragraph\nSecond\nparagraph"]
+pub(crate) fn html(s: Option<String>) -> HashSet<String> {
+    optional_to_hashset(s.map(|s| strip_html(&s)))
+}
+
+/// Example use-case:
+/// { "...": "The Way of Kings (The Stormlight Archive #1)" }
+///   -> Serde { "The Way of Kings (The Stormlight Archive #1)" }
+///   -> ["The Way of Kings"]
+pub(crate) fn title(s: &Option<String>) -> HashSet<String> {
+    optional_to_hashset(s.as_deref().map(|s| split_series(s).0.to_owned()))
+}
+
+/// Example use-case:
+/// { "...": "The Way of Kings (The Stormlight Archive #1)" }
+///   -> Serde { "The Way of Kings (The Stormlight Archive #1)" }
+///   -> ["The Stormlight Archive #1"]
+pub(crate) fn series(s: &Option<String>) -> HashSet<String> {
+    optional_to_hashset(
+        s.as_deref()
+            .and_then(|s| split_series(s).1.map(str::to_owned)),
+    )
+}
+
+/// Splits a trailing `(Series Name #N)` off of a title, which is how titles of
+/// books in a series are commonly written.
+///
+/// "The Way of Kings (The Stormlight Archive #1)" -> ("The Way of Kings", Some("The Stormlight Archive #1"))
+/// "The Way of Kings" -> ("The Way of Kings", None)
+pub(crate) fn split_series(s: &str) -> (&str, Option<&str>) {
+    let s = s.trim();
+
+    let series = s
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once('('))
+        .filter(|(title, series)| !title.trim().is_empty() && series.contains('#'));
+
+    match series {
+        Some((title, series)) => (title.trim(), Some(series.trim())),
+        None => (s, None),
+    }
+}
+
+/// Example use-case:
+/// { "...": [{ "key": "/languages/eng" }, { "key": "/languages/fre" }] }
+///   -> Serde { [{ "key": "/languages/eng" }, { "key": "/languages/fre" }] }
+///   -> ["en", "fr"]
+pub(crate) fn openlibrary_languages(
+    vec_hashmap: Option<Vec<HashMap<&str, &str>>>,
+) -> HashSet<String> {
+    hashset_fallback(vec_hashmap.map(|vec_hashmap| {
+        vec_hashmap
+            .into_iter()
+            .filter_map(|mut h| h.remove("key"))
+            .map(|key| normalize_language(key.trim_start_matches("/languages/")))
+            .collect()
+    }))
+}
+
+/// Example use-case:
+/// { "...": "English" } -> Serde { "English" } -> ["en"]
+pub(crate) fn language(s: Option<String>) -> HashSet<String> {
+    optional_to_hashset(s.map(|s| normalize_language(&s)))
+}
+
+/// Removes tags from an HTML fragment keeping its text,
+/// line breaks and paragraphs become newlines.
+///
+/// Descriptions only use a handful of inline and paragraph tags, so they are stripped
+/// without a full HTML parser to keep `scraper` out of builds without scraped sources.
+pub(crate) fn strip_html(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+
+        let end = rest[start..]
+            .find('>')
+            .map_or(rest.len(), |end| start + end);
+        let name = rest[start + 1..end]
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if let "br" | "p" | "div" | "li" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" = name.as_str() {
+            text.push('\n');
+        }
+
+        rest = rest.get(end + 1..).unwrap_or_default();
+    }
+    text.push_str(&decode_entities(rest));
+
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Decodes character references and the named entities common in descriptions,
+/// unknown entities are kept as is.
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| &rest[1..end]);
+        let c = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+
+        match (c, entity) {
+            (Some(c), Some(entity)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+/// Generational suffixes and titles that follow a name after a comma
+const NAME_SUFFIXES: &[&str] = &["jr", "sr", "ii", "iii", "iv", "phd", "md"];
+
+/// Words a multi-word surname may start with, e.g. `Le Guin` or `van Gogh`
+const SURNAME_PARTICLES: &[&str] = &[
+    "al", "bin", "da", "de", "del", "della", "der", "di", "du", "el", "ibn", "la", "le", "st.",
+    "ten", "ter", "van", "von",
+];
+
+/// Words naming an organization rather than a person, e.g. `Gates Foundation`
+const ORGANIZATION_WORDS: &[&str] = &[
+    "association",
+    "college",
+    "company",
+    "corporation",
+    "council",
+    "department",
+    "foundation",
+    "inc",
+    "institute",
+    "ltd",
+    "press",
+    "publishers",
+    "society",
+    "university",
+];
+
+/// Splits a string naming one or several authors into separate names,
+/// trimming whitespace and turning `Last, First` into `First Last`
+/// unless `keep_last_first` is set.
+///
+/// Names are split on `;`, and on ` and ` and ` & ` between names of several words.
+/// Comma separated parts are only split into names if none of them could be the surname
+/// of a `Last, First` name, i.e. a single word or words after particles like `Le` or `van`.
+/// Suffixes after a comma, single word names and organizations are kept as they are.
+///
+/// "Amal El-Mohtar, Max Gladstone" -> ["Amal El-Mohtar", "Max Gladstone"]
+/// "El-Mohtar, Amal" -> ["Amal El-Mohtar"]
+/// "Le Guin, Ursula K." -> ["Ursula K. Le Guin"]
+/// "Martin Luther King, Jr." -> ["Martin Luther King, Jr."]
+/// "Bill and Melinda Gates Foundation" -> ["Bill and Melinda Gates Foundation"]
+pub(crate) fn normalize_authors(s: &str, keep_last_first: bool) -> Vec<String> {
+    let is_suffix = |part: &str| {
+        NAME_SUFFIXES.contains(&part.trim().trim_end_matches('.').to_lowercase().as_str())
+    };
+    let words = |part: &str| part.split_whitespace().count();
+    let could_be_surname = |part: &str| {
+        let words = part.split_whitespace().collect::<Vec<_>>();
+        match words.split_last() {
+            Some((_, particles)) => particles
+                .iter()
+                .all(|word| SURNAME_PARTICLES.contains(&word.to_lowercase().as_str())),
+            None => false,
+        }
+    };
+    let is_organization = |s: &str| {
+        s.split(|c: char| !c.is_alphanumeric())
+            .any(|word| ORGANIZATION_WORDS.contains(&word.to_lowercase().as_str()))
+    };
+
+    s.split(';')
+        .flat_map(|s| {
+            let names = s
+                .split(" and ")
+                .flat_map(|s| s.split(" & "))
+                .collect::<Vec<_>>();
+
+            // `Simon and Schuster`, `Bill and Melinda Gates Foundation`
+            match is_organization(s) || names.iter().any(|name| words(name) == 1) {
+                true => vec![s],
+                false => names,
+            }
+        })
+        .flat_map(|s| {
+            let parts = s
+                .split(',')
+                .map(|part| part.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>();
+
+            match parts.as_slice() {
+                _ if is_organization(s) => vec![parts.join(", ")],
+                [name, suffix] if is_suffix(suffix) => vec![format!("{}, {}", name, suffix)],
+                [last, first] if could_be_surname(last) && !keep_last_first => {
+                    vec![format!("{} {}", first, last)]
+                }
+                parts
+                    if parts
+                        .iter()
+                        .all(|part| is_suffix(part) || !could_be_surname(part)) =>
+                {
+                    // a trailing suffix belongs to the name before it
+                    let mut names: Vec<String> = Vec::new();
+                    for part in parts {
+                        match (is_suffix(part), names.last_mut()) {
+                            (true, Some(name)) => *name = format!("{}, {}", name, part),
+                            _ => names.push(part.to_owned()),
+                        }
+                    }
+                    names
+                }
+                _ => vec![parts.join(", ")],
+            }
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Normalizes every author name, see [`normalize_authors`], keeping `Last, First` names
+/// in that order within [`with_config`] for a configuration with
+/// [`ReconConfig::keep_last_first`] set.
+pub(crate) fn authors<I, S>(names: I) -> HashSet<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let keep_last_first = KEEP_LAST_FIRST.with(Cell::get);
+
+    names
+        .into_iter()
+        .flat_map(|name| normalize_authors(name.as_ref(), keep_last_first))
+        .collect()
 }
 
 /// Example use-case:
@@ -88,8 +371,7 @@ pub(crate) fn vec_hashmap_field(
     hashset_fallback(vec_hashmap.map(|vec_hashmap| {
         vec_hashmap
             .into_iter()
-            .map(|mut h| h.remove(field))
-            .flatten()
+            .filter_map(|mut h| h.remove(field))
             .map(|s| s.to_owned())
             .collect()
     }))
@@ -130,18 +412,96 @@ pub(crate) fn vec_hashmap_field_split(
     hashset_fallback(vec_hashmap.map(|vec_hashmap| {
         vec_hashmap
             .into_iter()
-            .map(|mut h| h.remove(field))
-            .flatten()
-            .map(|s| {
-                s.split(',')
-                    .into_iter()
-                    .map(|s| s.trim().replace(" ", "-").to_lowercase())
-            })
-            .flatten()
+            .filter_map(|mut h| h.remove(field))
+            .flat_map(tags)
             .collect()
     }))
 }
 
+/// Example use-case:
+/// { "...": ["Fiction / Science Fiction / General", "Science fiction"] }
+///   -> Serde { ["Fiction / Science Fiction / General", "Science fiction"] }
+///   -> ["fiction", "science-fiction", "general"]
+pub(crate) fn vec_split(vec: Option<Vec<&str>>) -> HashSet<String> {
+    hashset_fallback(vec.map(|vec| vec.into_iter().flat_map(tags).collect()))
+}
+
+/// Splits a list of subjects on `,` and `/` (GoogleBooks separates category levels with it)
+/// into lowercase, hyphenated tags so the same subject from different sources merges
+/// into one entry.
+///
+/// "Fiction, science fiction, general" -> ["fiction", "science-fiction", "general"]
+pub(crate) fn tags(s: &str) -> Vec<String> {
+    s.split(&[',', '/'][..])
+        .map(|tag| {
+            tag.split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-")
+                .to_lowercase()
+        })
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Titles of the entries of a table of contents in order, given either as strings
+/// or as objects with a `title`.
+///
+/// [{ "level": 0, "title": "Chapter 1", "pagenum": "1" }] -> ["Chapter 1"]
+pub(crate) fn table_of_contents(value: Option<serde_json::Value>) -> Vec<String> {
+    let entries = match value {
+        Some(serde_json::Value::Array(entries)) => entries,
+        _ => return Vec::new(),
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| match entry {
+            serde_json::Value::String(title) => Some(title.as_str()),
+            entry => entry.get("title")?.as_str(),
+        })
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Example use-case:
+///
+/// "...": { "oclc": ["1100424998"], "...": "..." } -> field "oclc" -> ["1100424998"]
+pub(crate) fn openlibrary_identifier(
+    hashmap_vec: &Option<HashMap<&str, Vec<&str>>>,
+    field: &str,
+) -> HashSet<String> {
+    hashset_fallback(
+        hashmap_vec
+            .as_ref()
+            .and_then(|hashmap_vec| hashmap_vec.get(field))
+            .map(|v| v.iter().map(|s| s.trim().to_owned()).collect()),
+    )
+}
+
+/// Example use-case:
+///
+/// "...": { "isbn_13": ["isbn13"], "oclc": ["1100424998"], "goodreads": ["43352954"] }
+///   -> { "goodreads": ["43352954"] }
+///
+/// ISBNs and OCLC numbers have fields of their own.
+pub(crate) fn openlibrary_identifiers(
+    hashmap_vec: &Option<HashMap<&str, Vec<&str>>>,
+) -> HashMap<String, HashSet<String>> {
+    hashmap_vec
+        .iter()
+        .flatten()
+        .filter(|(k, _)| !k.starts_with("isbn_") && **k != "oclc")
+        .map(|(k, v)| {
+            (
+                k.to_string(),
+                v.iter().map(|s| s.trim().to_owned()).collect(),
+            )
+        })
+        .collect()
+}
+
 /// Example use-case:
 ///
 /// "...":
@@ -162,10 +522,8 @@ pub(crate) fn openlibrary_isbn10(
         hashmap_vec
             .iter()
             .filter(|(k, _)| k.starts_with("isbn_10"))
-            .map(|(_, v)| v)
-            .flatten()
-            .map(|s| Isbn10::from_str(s))
-            .flatten() // discarding `Err`
+            .flat_map(|(_, v)| v)
+            .flat_map(|s| Isbn10::from_str(s)) // discarding `Err`
             .collect()
     }))
 }
@@ -190,10 +548,8 @@ pub(crate) fn openlibrary_isbn13(
         hashmap_vec
             .iter()
             .filter(|(k, _)| k.starts_with("isbn_13"))
-            .map(|(_, v)| v)
-            .flatten()
-            .map(|s| Isbn13::from_str(s))
-            .flatten() // discarding `Err`
+            .flat_map(|(_, v)| v)
+            .flat_map(|s| Isbn13::from_str(s)) // discarding `Err`
             .collect()
     }))
 }
@@ -288,14 +644,78 @@ pub(crate) fn googlebooks_isbn10(
         hashmap_vec
             .iter()
             .filter(|h| h.get("type") == Some("ISBN_10").as_ref())
-            .map(|h| h.get("identifier"))
-            .flatten()
-            .map(|s| Isbn10::from_str(s))
-            .flatten() // discarding `Err`
+            .filter_map(|h| h.get("identifier"))
+            .flat_map(|s| Isbn10::from_str(s)) // discarding `Err`
             .collect()
     }))
 }
 
+/// Example use-case:
+///
+/// "...":
+///   [
+///     { "type": "ISBN_13", "identifier": "isbn13" },
+///     { "type": "ISSN", "identifier": "1234-5678" },
+///     { "type": "OTHER", "identifier": "OCLC:1100424998" }
+///   ],
+///
+///   -> [("issn", "1234-5678"), ("oclc", "1100424998")]
+fn googlebooks_catalog_identifiers<'a>(
+    hashmap_vec: &'a Option<Vec<HashMap<&str, &str>>>,
+) -> impl Iterator<Item = (String, String)> + 'a {
+    hashmap_vec.iter().flatten().filter_map(|h| {
+        let (kind, identifier) = (*h.get("type")?, *h.get("identifier")?);
+
+        let (catalog, identifier) = match kind {
+            "ISBN_10" | "ISBN_13" => return None,
+            // the catalog is given as a prefix, e.g. `OCLC:1100424998`
+            "OTHER" => {
+                let (catalog, identifier) = identifier.split_once(':')?;
+                (catalog.to_lowercase(), identifier)
+            }
+            kind => (kind.to_lowercase(), identifier),
+        };
+
+        Some((catalog, identifier.trim().to_owned()))
+    })
+}
+
+/// Example use-case:
+///
+/// "...": [{ "type": "OTHER", "identifier": "OCLC:1100424998" }] -> ["1100424998"]
+pub(crate) fn googlebooks_oclc(hashmap_vec: &Option<Vec<HashMap<&str, &str>>>) -> HashSet<String> {
+    googlebooks_catalog_identifiers(hashmap_vec)
+        .filter(|(catalog, _)| catalog == "oclc")
+        .map(|(_, identifier)| identifier)
+        .collect()
+}
+
+/// Example use-case:
+///
+/// "...":
+///   [
+///     { "type": "ISBN_13", "identifier": "isbn13" },
+///     { "type": "ISSN", "identifier": "1234-5678" },
+///     { "type": "OTHER", "identifier": "OCLC:1100424998" }
+///   ],
+///
+///   -> { "issn": ["1234-5678"] }
+///
+/// ISBNs and OCLC numbers have fields of their own.
+pub(crate) fn googlebooks_identifiers(
+    hashmap_vec: &Option<Vec<HashMap<&str, &str>>>,
+) -> HashMap<String, HashSet<String>> {
+    let mut identifiers = HashMap::<String, HashSet<String>>::new();
+
+    for (catalog, identifier) in googlebooks_catalog_identifiers(hashmap_vec) {
+        if catalog != "oclc" {
+            identifiers.entry(catalog).or_default().insert(identifier);
+        }
+    }
+
+    identifiers
+}
+
 /// Example use-case:
 ///
 /// "...":
@@ -399,10 +819,8 @@ pub(crate) fn googlebooks_isbn13(
         hashmap_vec
             .iter()
             .filter(|h| h.get("type") == Some("ISBN_13").as_ref())
-            .map(|h| h.get("identifier"))
-            .flatten()
-            .map(|s| Isbn13::from_str(s))
-            .flatten() // discarding `Err`
+            .filter_map(|h| h.get("identifier"))
+            .flat_map(|s| Isbn13::from_str(s)) // discarding `Err`
             .collect()
     }))
 }
@@ -411,26 +829,432 @@ pub(crate) fn googlebooks_isbn13(
 ///
 /// { "...": "2019-07-16" }
 ///
-/// -> [NaiveDate(2019-07-16)]
+/// -> [PubDate::Full(2019-07-16)]
+///
+/// { "...": "May 7, 2016" } or { "...": "7 May 2016" }
+///
+/// -> [PubDate::Full(2016-05-07)]
 ///
-/// { "...": "May 07 16" }
+/// { "...": "2011-03" } or { "...": "March 2011" }
 ///
-/// -> [NaiveDate(2016-05-07)]
+/// -> [PubDate::YearMonth(2011, 3)]
+///
+/// { "...": "2011" }
+///
+/// -> [PubDate::Year(2011)]
 ///
 /// { "...": "Not a date" }
 ///
 /// -> []
-pub(crate) fn publication_date(s: Option<&str>) -> HashSet<NaiveDate> {
-    let possible_formats = ["%B %d, %Y", "%Y-%m-%d", "%B, %d %Y"];
+pub(crate) fn publication_date(s: Option<&str>) -> HashSet<PubDate> {
+    // GoogleBooks uses ISO 8601, OpenLibrary and Amazon mostly write dates out in English
+    let possible_formats = [
+        "%Y-%m-%d",   // 2019-07-16
+        "%B %d, %Y",  // July 16, 2019
+        "%b %d, %Y",  // Jul 16, 2019
+        "%d %B %Y",   // 16 July 2019
+        "%b. %d, %Y", // Jul. 16, 2019
+    ];
 
-    match s {
-        Some(s) => possible_formats
-            .iter()
-            .map(|fmt| NaiveDate::parse_from_str(s, fmt))
-            .filter(|s| s.is_ok())
-            .map(|s| s.unwrap())
-            .collect::<HashSet<NaiveDate>>(),
+    // Dates missing a day or a month, completed with the first one to parse them
+    // with `NaiveDate` and then narrowed back to whether their month was given.
+    let partial_formats = [
+        ("", "-01", "%Y-%m-%d", true),     // 2011-03
+        ("01 ", "", "%d %B %Y", true),     // March 2011
+        ("", "-01-01", "%Y-%m-%d", false), // 2011
+    ];
+
+    match s.map(str::trim) {
+        Some(s) => {
+            let dates = DATE_FORMATS.with(|extra_formats| {
+                possible_formats
+                    .iter()
+                    .copied()
+                    .chain(extra_formats.borrow().iter().map(String::as_str))
+                    .filter_map(|fmt| NaiveDate::parse_from_str(s, fmt).ok())
+                    .collect::<Vec<_>>()
+            });
+            let dates = dates
+                .into_iter()
+                .map(PubDate::Full)
+                .collect::<HashSet<PubDate>>();
+
+            if !dates.is_empty() {
+                return dates;
+            }
+
+            partial_formats
+                .iter()
+                .filter_map(|(prefix, suffix, fmt, has_month)| {
+                    let date =
+                        NaiveDate::parse_from_str(&format!("{}{}{}", prefix, s, suffix), fmt)
+                            .ok()?;
+
+                    Some(match has_month {
+                        true => PubDate::YearMonth(date.year(), date.month()),
+                        false => PubDate::Year(date.year()),
+                    })
+                })
+                .collect::<HashSet<PubDate>>()
+        }
 
         None => HashSet::new(),
     }
 }
+
+/// ISO 639-1 code, ISO 639-2 bibliographic and terminology codes (same if they don't differ)
+/// and English name of commonly catalogued languages
+const LANGUAGES: &[(&str, &str, &str, &str)] = &[
+    ("ar", "ara", "ara", "arabic"),
+    ("bn", "ben", "ben", "bengali"),
+    ("ca", "cat", "cat", "catalan"),
+    ("cs", "cze", "ces", "czech"),
+    ("da", "dan", "dan", "danish"),
+    ("de", "ger", "deu", "german"),
+    ("el", "gre", "ell", "greek"),
+    ("en", "eng", "eng", "english"),
+    ("es", "spa", "spa", "spanish"),
+    ("fa", "per", "fas", "persian"),
+    ("fi", "fin", "fin", "finnish"),
+    ("fr", "fre", "fra", "french"),
+    ("he", "heb", "heb", "hebrew"),
+    ("hi", "hin", "hin", "hindi"),
+    ("hu", "hun", "hun", "hungarian"),
+    ("id", "ind", "ind", "indonesian"),
+    ("it", "ita", "ita", "italian"),
+    ("ja", "jpn", "jpn", "japanese"),
+    ("ko", "kor", "kor", "korean"),
+    ("la", "lat", "lat", "latin"),
+    ("nl", "dut", "nld", "dutch"),
+    ("no", "nor", "nor", "norwegian"),
+    ("pl", "pol", "pol", "polish"),
+    ("pt", "por", "por", "portuguese"),
+    ("ro", "rum", "ron", "romanian"),
+    ("ru", "rus", "rus", "russian"),
+    ("sv", "swe", "swe", "swedish"),
+    ("th", "tha", "tha", "thai"),
+    ("tr", "tur", "tur", "turkish"),
+    ("uk", "ukr", "ukr", "ukrainian"),
+    ("ur", "urd", "urd", "urdu"),
+    ("vi", "vie", "vie", "vietnamese"),
+    ("zh", "chi", "zho", "chinese"),
+];
+
+/// Maps a language name or ISO 639-1/639-2 (MARC) code to its ISO 639-1 code,
+/// so the same language reported differently by each source merges into one entry.
+/// Languages not recognized are returned trimmed but otherwise unchanged.
+///
+/// "English" -> "en", "eng" -> "en", "EN" -> "en", "Klingon" -> "Klingon"
+pub(crate) fn normalize_language(s: &str) -> String {
+    let s = s.trim();
+    let lowercase = s.to_lowercase();
+
+    LANGUAGES
+        .iter()
+        .find(|(code, bibliographic, terminology, name)| {
+            [code, bibliographic, terminology, name].contains(&&lowercase.as_str())
+        })
+        .map(|(code, ..)| (*code).to_owned())
+        .unwrap_or_else(|| s.to_owned())
+}
+
+/// BISAC subject headings of common tags, several tags may share a heading.
+/// <https://www.bisg.org/complete-bisac-subject-headings-list>
+const BISAC: &[(&str, &str)] = &[
+    ("fiction", "FIC000000"),
+    ("action-&-adventure", "FIC002000"),
+    ("adventure", "FIC002000"),
+    ("classics", "FIC004000"),
+    ("fantasy", "FIC009000"),
+    ("epic-fantasy", "FIC009020"),
+    ("fairy-tales", "FIC010000"),
+    ("mythology", "FIC010000"),
+    ("ghost-stories", "FIC012000"),
+    ("historical-fiction", "FIC014000"),
+    ("horror", "FIC015000"),
+    ("humorous", "FIC016000"),
+    ("literary-fiction", "FIC019000"),
+    ("literary", "FIC019000"),
+    ("mystery", "FIC022000"),
+    ("mystery-&-detective", "FIC022000"),
+    ("detective", "FIC022000"),
+    ("romance", "FIC027000"),
+    ("science-fiction", "FIC028000"),
+    ("sci-fi", "FIC028000"),
+    ("suspense", "FIC030000"),
+    ("thriller", "FIC031000"),
+    ("thrillers", "FIC031000"),
+    ("dystopian", "FIC055000"),
+    ("young-adult-fiction", "YAF000000"),
+    ("young-adult", "YAF000000"),
+    ("juvenile-fiction", "JUV000000"),
+    ("antiques-&-collectibles", "ANT000000"),
+    ("architecture", "ARC000000"),
+    ("art", "ART000000"),
+    ("biography", "BIO000000"),
+    ("biography-&-autobiography", "BIO000000"),
+    ("autobiography", "BIO000000"),
+    ("memoir", "BIO000000"),
+    ("body-mind-&-spirit", "OCC000000"),
+    ("business-&-economics", "BUS000000"),
+    ("business", "BUS000000"),
+    ("economics", "BUS000000"),
+    ("comics-&-graphic-novels", "CGN000000"),
+    ("graphic-novels", "CGN000000"),
+    ("comics", "CGN000000"),
+    ("computers", "COM000000"),
+    ("cooking", "CKB000000"),
+    ("crafts-&-hobbies", "CRA000000"),
+    ("design", "DES000000"),
+    ("drama", "DRA000000"),
+    ("education", "EDU000000"),
+    ("family-&-relationships", "FAM000000"),
+    ("foreign-language-study", "FOR000000"),
+    ("games-&-activities", "GAM000000"),
+    ("gardening", "GAR000000"),
+    ("health-&-fitness", "HEA000000"),
+    ("history", "HIS000000"),
+    ("house-&-home", "HOM000000"),
+    ("humor", "HUM000000"),
+    ("language-arts-&-disciplines", "LAN000000"),
+    ("law", "LAW000000"),
+    ("literary-collections", "LCO000000"),
+    ("literary-criticism", "LIT000000"),
+    ("mathematics", "MAT000000"),
+    ("medical", "MED000000"),
+    ("music", "MUS000000"),
+    ("nature", "NAT000000"),
+    ("performing-arts", "PER000000"),
+    ("pets", "PET000000"),
+    ("philosophy", "PHI000000"),
+    ("photography", "PHO000000"),
+    ("poetry", "POE000000"),
+    ("political-science", "POL000000"),
+    ("politics", "POL000000"),
+    ("psychology", "PSY000000"),
+    ("reference", "REF000000"),
+    ("religion", "REL000000"),
+    ("science", "SCI000000"),
+    ("self-help", "SEL000000"),
+    ("social-science", "SOC000000"),
+    ("sports-&-recreation", "SPO000000"),
+    ("study-aids", "STU000000"),
+    ("technology-&-engineering", "TEC000000"),
+    ("transportation", "TRA000000"),
+    ("travel", "TRV000000"),
+    ("true-crime", "TRU000000"),
+];
+
+/// Maps tags, as normalized by [`tags`], to the codes of their BISAC subject headings.
+/// Tags without a known heading are left out.
+///
+/// ["science-fiction", "space-opera"] -> ["FIC028000"]
+pub(crate) fn tags_to_bisac<'a, I>(tags: I) -> HashSet<String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    tags.into_iter()
+        .filter_map(|tag| {
+            BISAC
+                .iter()
+                .find(|(known, _)| known == tag)
+                .map(|(_, code)| (*code).to_owned())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    #[test]
+    fn merges_tags_across_sources() {
+        use super::{vec_hashmap_field_split, vec_split};
+        use std::collections::HashMap;
+
+        let google_books = vec_split(Some(vec![
+            "Fiction / Science Fiction / General",
+            "Science Fiction",
+        ]));
+
+        let open_library = vec_hashmap_field_split(
+            Some(vec![
+                HashMap::from([("name", "science fiction")]),
+                HashMap::from([("name", "Fiction, science fiction, general")]),
+            ]),
+            "name",
+        );
+
+        assert_eq!(google_books, open_library);
+        assert_eq!(google_books.len(), 3);
+        assert!(google_books.contains("science-fiction"));
+    }
+
+    #[test]
+    fn splits_series() {
+        use super::split_series;
+
+        assert_eq!(
+            split_series("The Way of Kings (The Stormlight Archive #1)"),
+            ("The Way of Kings", Some("The Stormlight Archive #1"))
+        );
+        assert_eq!(
+            split_series("The Way of Kings (Illustrated Edition)"),
+            ("The Way of Kings (Illustrated Edition)", None)
+        );
+        assert_eq!(split_series("(Series #1)"), ("(Series #1)", None));
+    }
+
+    #[test]
+    fn normalizes_language() {
+        use super::normalize_language;
+
+        assert_eq!(normalize_language("en"), "en");
+        assert_eq!(normalize_language(" English "), "en");
+        assert_eq!(normalize_language("eng"), "en");
+        assert_eq!(normalize_language("ger"), "de");
+        assert_eq!(normalize_language("deu"), "de");
+        assert_eq!(normalize_language("Klingon"), "Klingon");
+    }
+
+    #[test]
+    fn maps_tags_to_bisac() {
+        use super::{tags, tags_to_bisac};
+
+        let tags = tags("Fiction / Science Fiction / General, Space Opera, Sci-Fi");
+        let codes = tags_to_bisac(&tags);
+
+        assert_eq!(codes.len(), 2);
+        assert!(codes.contains("FIC000000"));
+        assert!(codes.contains("FIC028000"));
+    }
+
+    #[test]
+    fn strips_html() {
+        use super::strip_html;
+
+        assert_eq!(
+            strip_html("<p>First <i>paragraph</i> &amp; more</p><p>Second<br>paragraph</p>"),
+            "First paragraph & more\nSecond\nparagraph"
+        );
+        assert_eq!(strip_html("No markup"), "No markup");
+        assert_eq!(
+            strip_html("<div class=\"x\">R&#233;sum&eacute; &#x26; <b>bold</b></div>"),
+            "R\u{e9}sum&eacute; & bold"
+        );
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn normalizes_authors() {
+        use super::normalize_authors;
+
+        let cases: &[(&str, &[&str])] = &[
+            ("  Amal El-Mohtar ", &["Amal El-Mohtar"]),
+            ("El-Mohtar, Amal", &["Amal El-Mohtar"]),
+            ("Tolkien, J. R. R.", &["J. R. R. Tolkien"]),
+            (
+                "Amal El-Mohtar, Max Gladstone",
+                &["Amal El-Mohtar", "Max Gladstone"],
+            ),
+            (
+                "Amal El-Mohtar and Max Gladstone",
+                &["Amal El-Mohtar", "Max Gladstone"],
+            ),
+            (
+                "El-Mohtar, Amal; Gladstone, Max",
+                &["Amal El-Mohtar", "Max Gladstone"],
+            ),
+            ("Martin Luther King, Jr.", &["Martin Luther King, Jr."]),
+            (
+                "Martin Luther King, Jr., Coretta Scott King",
+                &["Martin Luther King, Jr.", "Coretta Scott King"],
+            ),
+            ("Homer", &["Homer"]),
+            ("Le Guin, Ursula K.", &["Ursula K. Le Guin"]),
+            ("Van Gogh, Vincent Willem", &["Vincent Willem Van Gogh"]),
+            (
+                "Le Guin, Ursula K.; Gladstone, Max",
+                &["Ursula K. Le Guin", "Max Gladstone"],
+            ),
+            (
+                "Bill and Melinda Gates Foundation",
+                &["Bill and Melinda Gates Foundation"],
+            ),
+            ("Simon & Schuster", &["Simon & Schuster"]),
+            (
+                "University of California, Berkeley",
+                &["University of California, Berkeley"],
+            ),
+        ];
+
+        for (s, names) in cases {
+            assert_eq!(normalize_authors(s, false), *names, "{}", s);
+        }
+
+        assert_eq!(
+            normalize_authors("El-Mohtar, Amal", true),
+            ["El-Mohtar, Amal"]
+        );
+        assert_eq!(
+            normalize_authors("Le Guin, Ursula K.", true),
+            ["Le Guin, Ursula K."]
+        );
+    }
+
+    #[test]
+    fn keeps_configured_author_order() {
+        use super::{authors, with_config};
+        use crate::recon::ReconConfig;
+
+        let config = ReconConfig::new().keep_last_first(true);
+        assert!(authors(["El-Mohtar, Amal"]).contains("Amal El-Mohtar"));
+        assert!(with_config(&config, || authors(["El-Mohtar, Amal"])).contains("El-Mohtar, Amal"));
+        assert!(authors(["El-Mohtar, Amal"]).contains("Amal El-Mohtar"));
+    }
+
+    #[test]
+    fn parses_publication_date() {
+        use super::{publication_date, with_config};
+        use crate::metadata::PubDate;
+        use crate::recon::ReconConfig;
+
+        let full = |y, m, d| PubDate::Full(date(y, m, d));
+
+        assert!(publication_date(Some("2019-07-16")).contains(&full(2019, 7, 16)));
+        // as found in GoogleBooks, OpenLibrary and Amazon responses
+        for s in [
+            "2019-07-16",
+            "July 16, 2019",
+            "Jul 16, 2019",
+            "Jul. 16, 2019",
+            "16 July 2019",
+            "16 Jul 2019",
+        ] {
+            assert_eq!(
+                publication_date(Some(s)).into_iter().collect::<Vec<_>>(),
+                [full(2019, 7, 16)],
+                "{}",
+                s
+            );
+        }
+        assert!(publication_date(Some("Mar 2009")).contains(&PubDate::YearMonth(2009, 3)));
+        assert!(publication_date(Some("2009-03")).contains(&PubDate::YearMonth(2009, 3)));
+        assert!(publication_date(Some("March 2009")).contains(&PubDate::YearMonth(2009, 3)));
+        assert!(publication_date(Some("2011")).contains(&PubDate::Year(2011)));
+        assert!(!publication_date(Some("2011")).contains(&full(2011, 1, 1)));
+
+        let config = ReconConfig::new().date_format("%d.%m.%Y");
+        assert!(publication_date(Some("16.07.2019")).is_empty());
+        assert!(
+            with_config(&config, || publication_date(Some("16.07.2019")))
+                .contains(&full(2019, 7, 16))
+        );
+        assert!(publication_date(Some("16.07.2019")).is_empty());
+        assert!(publication_date(Some("Not a date")).is_empty());
+        assert!(publication_date(None).is_empty());
+    }
+}